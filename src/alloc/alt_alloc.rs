@@ -7,12 +7,13 @@ use super::AllocError;
 /// can be used to implement/wrap a custom allocator in a no_std environment.
 /// It mirrors the unstable allocator API at the moment.
 ///
-/// This mirrors the safety requirements of the allocator API:
-/// <https://doc.rust-lang.org/std/alloc/trait.Allocator.html>
-///
-///
 /// If the allocator api is ever marked as stable this trait probably can
 /// be removed.
+///
+/// # Safety
+///
+/// This mirrors the safety requirements of the allocator API:
+/// <https://doc.rust-lang.org/std/alloc/trait.Allocator.html>
 pub unsafe trait AltAllocator {
     /// Allocates a chunk of memory with the given layout.
     ///
@@ -32,6 +33,8 @@ pub unsafe trait AltAllocator {
 
     /// Deallocates the chunk of memory pointed at by`ptr`
     ///
+    /// # Safety
+    ///
     /// This memory must have only been allocated by this allocator.
     /// The layout must match the layout provided when the chunk was
     /// allocated.
@@ -39,6 +42,8 @@ pub unsafe trait AltAllocator {
 
     /// Grows the memory pointed at by `old_ptr` to the new layout.
     ///
+    /// # Safety
+    ///
     /// The new layout must be larger than the old layout.
     ///
     /// If this fails the old ptr must still will be valid. If it succeeds
@@ -61,6 +66,10 @@ pub unsafe trait AltAllocator {
     }
 
     /// Behaves just like `grow` but the new memory will be zeroed.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as `grow` apply.
     unsafe fn grow_zeroed(
         &self,
         old_ptr: NonNull<u8>,
@@ -78,6 +87,9 @@ pub unsafe trait AltAllocator {
     }
 
     /// Shrinks the memory pointed at by `old_ptr` to the new layout.
+    ///
+    /// # Safety
+    ///
     /// The new layout must be smaller than the old layout.
     ///
     /// If this fails the old ptr will still be valid. If it succeeds
@@ -1,6 +1,12 @@
 mod array;
 mod inner;
+pub mod iter;
+mod slice;
+mod small;
+mod sort;
 #[cfg(test)]
 mod tests;
 
 pub use array::FlexArr;
+pub use slice::FlexSlice;
+pub use small::SmallFlexArr;
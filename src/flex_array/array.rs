@@ -1,20 +1,54 @@
 use core::alloc::Layout;
+use core::cmp::Ordering;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::iter::Copied;
+use core::iter::FusedIterator;
 use core::marker::PhantomData;
+use core::mem;
+use core::mem::MaybeUninit;
 use core::mem::forget;
+use core::ops::Bound;
 use core::ops::Index;
 use core::ops::IndexMut;
+use core::ops::Range;
+use core::ops::RangeBounds;
+use core::ops::RangeFrom;
+use core::ops::RangeFull;
+use core::ops::RangeInclusive;
+use core::ops::RangeTo;
+use core::ops::RangeToInclusive;
 use core::ptr;
 use core::ptr::NonNull;
 use core::slice;
 
+#[cfg(feature = "alloc_api2")]
+use allocator_api2::alloc::Allocator as Alloc2;
+#[cfg(feature = "alloc_api2")]
+use allocator_api2::boxed::Box as Api2Box;
+
+use super::FlexSlice;
 use super::inner::Inner;
+use super::inner::layout_array;
+use super::inner::size_is_zst;
+use super::iter::Chunks;
+use super::iter::Drain;
+use super::iter::IntoIter;
+use super::iter::IntoRChunks;
+use super::iter::Windows;
+use super::sort;
 use crate::alloc::AltAllocator;
 #[cfg(feature = "std_alloc")]
 use crate::alloc::Global;
+use crate::types::CollectError;
+use crate::types::CompactPolicy;
 use crate::types::ErrorReason;
 use crate::types::FlexArrErr;
 use crate::types::FlexArrResult;
+use crate::types::FlexIndex;
 use crate::types::LengthType;
+use crate::types::ReserveError;
+use crate::types::ReserveInfo;
 
 macro_rules! define_array_struct {
     ($($global:ty)?) => {
@@ -31,7 +65,6 @@ macro_rules! define_array_struct {
         ///
         /// Lastly, the allocator API is not stable yet, so this crate provides and alternate trait `AltAllocator`
         /// that works like `Allocator` the trait can be used with `FlexArr` to specify the allocator to use.
-        #[derive(Debug)]
         pub struct FlexArr<T, A: AltAllocator $(= $global)?, L: LengthType = u32>
         where
             usize: TryFrom<L>,
@@ -57,6 +90,9 @@ where
     ///
     /// This function initializes the array without performing any memory allocation. The resulting
     /// `FlexArr` is empty, and memory will only be allocated when elements are added.
+    ///
+    /// Since this is a `const fn` it can initialize a `const` or `static` item, as long as the
+    /// allocator itself can be built in a const context, like `Global` or any unit struct.
     pub const fn new_in(alloc: A) -> Self {
         return Self {
             inner: Inner::new_in::<T>(alloc),
@@ -68,6 +104,12 @@ where
     ///
     /// This function attempts to allocate enough memory for the desired capacity during initialization.
     /// If the allocation fails, a `FlexArrErr` is returned.
+    ///
+    /// Just like `Vec::with_capacity()`, a `capacity` of zero does not allocate and is the same as
+    /// `new_in()`. Zero sized types never allocate and always report the maximum capacity.
+    ///
+    /// If the allocator hands back a larger block than was asked for, such as an allocator that
+    /// rounds up to size classes, the extra room is kept and `capacity()` may be more than `capacity`.
     pub fn with_capacity_in(alloc: A, capacity: L) -> FlexArrResult<Self> {
         let mut inner = Inner::new_in::<T>(alloc);
         inner.expand_capacity_to(capacity, Self::LAYOUT)?;
@@ -77,6 +119,137 @@ where
         });
     }
 
+    /// Creates a copy of the `FlexArr` by cloning each element, using a clone of its allocator.
+    ///
+    /// Only enough memory for the length is allocated, not the whole capacity of the
+    /// original, so clones stay as tight as possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the memory for the copy could not be allocated.
+    pub fn try_clone(&self) -> FlexArrResult<Self>
+    where
+        T: Clone,
+        A: Clone,
+    {
+        let mut arr = self.new_buffer(self.len())?;
+        for item in self.as_slice() {
+            arr.push_within_capacity(item.clone());
+        }
+        return Ok(arr);
+    }
+
+    /// Creates a new `FlexArr` holding the elements of this one repeated `n` times, like
+    /// `[T]::repeat()`. The whole result is allocated at once using a clone of the allocator.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of `CapacityOverflow` if the new length does not
+    /// fit into the length type, or `UsizeOverflow` if it does not fit into a `usize`. This
+    /// is checked before anything is allocated. Otherwise an error is returned if the memory
+    /// could not be allocated.
+    pub fn repeat(&self, n: L) -> FlexArrResult<Self>
+    where
+        T: Copy,
+        A: Clone,
+    {
+        let Some(total) = self.len().checked_mul(n) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        let Ok(usz_total) = usize::try_from(total) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        let mut arr = self.new_buffer(total)?;
+        if usz_total == 0 || Self::is_zst() {
+            unsafe { arr.set_len(total) };
+            return Ok(arr);
+        }
+
+        // Copy the elements once, then keep doubling what has been written so far.
+        let len = self.len_usize();
+        let dst = arr.as_mut_ptr();
+        unsafe { ptr::copy_nonoverlapping(self.as_ptr(), dst, len) };
+        let mut filled = len;
+        while filled < usz_total {
+            let count = filled.min(usz_total - filled);
+            unsafe { ptr::copy_nonoverlapping(dst, dst.add(filled), count) };
+            filled += count;
+        }
+        unsafe { arr.set_len(total) };
+        return Ok(arr);
+    }
+
+    /// Creates a new `FlexArr` using the provided allocator from a source whose items can fail.
+    ///
+    /// Space is reserved using the lower bound of the iterator's size hint, and collection
+    /// stops at the first `Err` the source produces. Any elements collected before that
+    /// point are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CollectError::Source` with the first error the source produced, or
+    /// `CollectError::Array` if the `FlexArr` failed to grow.
+    pub fn try_collect_in<E, I>(iter: I, alloc: A) -> Result<Self, CollectError<E>>
+    where
+        I: IntoIterator<Item = Result<T, E>>,
+    {
+        let iter = iter.into_iter();
+        let mut arr = Self::new_in(alloc);
+        arr.reserve_usize(iter.size_hint().0)?;
+
+        for item in iter {
+            match item {
+                Ok(item) => arr.push(item)?,
+                Err(err) => return Err(CollectError::Source(err)),
+            }
+        }
+        return Ok(arr);
+    }
+
+    /// Creates a new `FlexArr` using the provided allocator containing the items of `iter`
+    /// in reverse order.
+    ///
+    /// The items are taken from the back of the iterator, so this avoids having to collect
+    /// the items and then reverse them afterwards. Space is reserved using the lower bound
+    /// of the iterator's size hint.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory allocation fails or the number of items does
+    /// not fit into the `LengthType`. Any items collected so far are dropped.
+    pub fn from_iter_rev_in<I>(iter: I, alloc: A) -> FlexArrResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        let iter = iter.into_iter();
+        let mut arr = Self::new_in(alloc);
+        arr.reserve_usize(iter.size_hint().0)?;
+
+        for item in iter.rev() {
+            arr.push(item)?;
+        }
+        return Ok(arr);
+    }
+
+    /// Creates a new `FlexArr` using the provided allocator containing the items of `iter`.
+    ///
+    /// This reserves space the same way as `try_extend()`, so when the iterator reports its
+    /// exact length only a single allocation is made.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory allocation fails or the number of items does
+    /// not fit into the `LengthType`. Any items collected so far are dropped.
+    pub fn try_from_iter_in<I>(iter: I, alloc: A) -> FlexArrResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut arr = Self::new_in(alloc);
+        arr.try_extend(iter)?;
+        return Ok(arr);
+    }
+
     /// Ensures that `FlexArr` has enough capacity to store at least `additional` more elements.
     /// It may reserve more than `additional` elements. You can use this if you anticipate
     /// how many elements need to be inserted to avoid frequent reallocations.
@@ -89,13 +262,14 @@ where
     /// the required capacity.
     #[inline]
     pub fn reserve(&mut self, additional: L) -> FlexArrResult<()> {
-        let needed = self.capacity_needed(additional)?;
-        let cap = self.capacity();
-        if cap >= needed {
+        // Fast path for when there is already plenty of room, which skips the overflow
+        // check on the new length. The length is never more than the capacity, so this
+        // subtraction never fails.
+        if additional <= self.capacity() - self.len() {
             return Ok(());
         }
-
-        return self.inner.expand_capacity_at_least(needed, Self::LAYOUT);
+        let needed = self.capacity_needed(additional)?;
+        return self.grow_amortized(needed);
     }
 
     /// Ensures that `FlexArr` can store at least `additional` more elements,
@@ -116,11 +290,133 @@ where
         return self.reserve(add);
     }
 
+    /// Ensures that `FlexArr` has enough capacity to store at least `additional` more elements,
+    /// just like `reserve()`, but the error returned says directly what went wrong.
+    ///
+    /// This is useful for code that handles running out of memory. For example, one could
+    /// free some caches and try again on `ReserveError::AllocFailed` knowing exactly what
+    /// `Layout` was asked for, while `ReserveError::CapacityOverflow` can never succeed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReserveError::AllocFailed` with the attempted `Layout` if the allocator
+    /// failed, or `ReserveError::CapacityOverflow` if the required capacity can not
+    /// be represented.
+    pub fn reserve_checked(&mut self, additional: L) -> Result<(), ReserveError> {
+        let Ok(needed) = self.capacity_needed(additional) else {
+            return Err(ReserveError::CapacityOverflow);
+        };
+        let cap = self.capacity();
+        if needed <= cap {
+            return Ok(());
+        }
+
+        let new_cap = Inner::<A, L>::amortized_capacity(cap, needed);
+        let Err(err) = self.inner.expand_capacity_to(new_cap, Self::LAYOUT) else {
+            return Ok(());
+        };
+        if err.reason() != ErrorReason::AllocFailure {
+            return Err(ReserveError::CapacityOverflow);
+        }
+        // The layout was already successfully created once to attempt the allocation.
+        let Ok(layout) = layout_array(Self::LAYOUT, new_cap.as_usize()) else {
+            return Err(ReserveError::CapacityOverflow);
+        };
+        return Err(ReserveError::AllocFailed(layout));
+    }
+
+    /// Ensures that `FlexArr` has enough capacity to store at least `additional` more elements,
+    /// just like `reserve()`, and then reports the resulting capacity and whether the elements
+    /// were moved to a new address.
+    ///
+    /// This is useful for adaptive buffering, or for refreshing pointers into the `FlexArr`
+    /// only when they were actually invalidated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    pub fn reserve_info(&mut self, additional: L) -> FlexArrResult<ReserveInfo<L>> {
+        let before = self.as_ptr();
+        self.reserve(additional)?;
+        return Ok(ReserveInfo {
+            capacity:    self.capacity(),
+            reallocated: before != self.as_ptr(),
+        });
+    }
+
+    /// Ensures that `FlexArr` has enough capacity to store at least `additional` more elements,
+    /// just like `reserve()`, but gives a chance to recover when the allocator fails.
+    ///
+    /// On an allocation failure `on_fail` is called. If it returns `true`, meaning it freed
+    /// up some memory, the reservation is attempted again. This repeats at most
+    /// `RESERVE_RETRY_LIMIT` times. Other errors such as overflows are returned right away
+    /// since retrying would never help.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if `on_fail` returns `false`, the retries are exhausted, or the
+    /// reservation fails for any other reason.
+    pub fn reserve_with_retry<F>(&mut self, additional: L, mut on_fail: F) -> FlexArrResult<()>
+    where
+        F: FnMut() -> bool,
+    {
+        let mut attempts = 0;
+        loop {
+            let Err(err) = self.reserve(additional) else {
+                return Ok(());
+            };
+            if err.reason() != ErrorReason::AllocFailure || attempts >= Self::RESERVE_RETRY_LIMIT {
+                return Err(err);
+            }
+            if !on_fail() {
+                return Err(err);
+            }
+            attempts += 1;
+        }
+    }
+
+    /// Ensures that `FlexArr` can store at least `additional` more elements like `reserve()`,
+    /// and that the memory for those `additional` elements past the length is zeroed.
+    ///
+    /// When the memory has to grow, the allocator's zeroed growth is used so only the newly
+    /// added memory gets zeroed by it, and the existing elements are kept. Any spare capacity
+    /// that already existed is zeroed here. This is meant for types where all zero bytes is a
+    /// valid value, so the elements can then be made part of the array with `set_len()` style
+    /// workflows like `reserve_and_fill()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    pub fn reserve_zeroed(&mut self, additional: L) -> FlexArrResult<()> {
+        let needed = self.capacity_needed(additional)?;
+        let cap = self.capacity();
+        if needed > cap {
+            let new_cap = Inner::<A, L>::amortized_capacity(cap, needed);
+            self.inner.expand_capacity_zeroed_to(new_cap, Self::LAYOUT)?;
+        }
+
+        // Anything past the old capacity was just zeroed by the allocator.
+        let len = self.len().as_usize();
+        let end = needed.min(cap).as_usize();
+        if end > len {
+            let ptr = unsafe { self.as_mut_ptr().add(len) };
+            unsafe { ptr::write_bytes(ptr, 0, end - len) };
+        }
+        return Ok(());
+    }
+
     /// Ensures that `FlexArr` has exactly enough capacity for `additional` more elements.
     ///
-    /// While the allocator may allocate slightly more memory than requested, this method
-    /// aims to match the exact required capacity. Use this when you know the exact number
-    /// of elements to be inserted to minimize wasted memory.
+    /// Unlike `reserve()` no extra room is added for future growth, the allocator is asked
+    /// for exactly `len + additional` elements. Use this when you know the exact number of
+    /// elements to be inserted to minimize wasted memory.
+    ///
+    /// Afterwards `capacity()` is at least `len + additional`. It is only more when the
+    /// allocator returned a larger block than asked for, since those whole extra elements
+    /// are adopted into the capacity. With an allocator that does not round up, such as
+    /// `Global`, the capacity is exactly `len + additional`.
     ///
     /// If the current capacity is already sufficient, this method does nothing.
     ///
@@ -138,22 +434,169 @@ where
         return self.inner.expand_capacity_to(needed, Self::LAYOUT);
     }
 
+    /// Ensures that `FlexArr` can store up to `additional` more elements, but instead of
+    /// failing when the length plus `additional` does not fit in the `LengthType`, the
+    /// target capacity is clamped to the maximum value of `L`.
+    ///
+    /// This suits best effort buffering where reaching the most the length type can hold
+    /// is good enough. Afterwards, check `capacity()` to see how much room there is.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    #[inline]
+    pub fn reserve_saturating(&mut self, additional: L) -> FlexArrResult<()> {
+        let needed = self.len().saturating_add(additional);
+        return self.grow_amortized(needed);
+    }
+
+    /// Ensures that the capacity of the `FlexArr` is at least `total`, no matter the length.
+    /// It may reserve more using the same growth strategy as `reserve()`.
+    ///
+    /// This saves working out `total - len` at the call site when the target capacity is
+    /// already known. If the capacity is already at least `total`, this method does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    #[inline]
+    pub fn reserve_total(&mut self, total: L) -> FlexArrResult<()> {
+        return self.grow_amortized(total);
+    }
+
+    /// Ensures that the capacity of the `FlexArr` is at least `total` like `reserve_total()`,
+    /// but grows to exactly `total` when it needs to grow.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    pub fn reserve_total_exact(&mut self, total: L) -> FlexArrResult<()> {
+        if total <= self.capacity() {
+            return Ok(());
+        }
+        return self.inner.expand_capacity_to(total, Self::LAYOUT);
+    }
+
+    /// Ensures that `FlexArr` has enough capacity to store at least `additional` more elements,
+    /// growing the capacity to the next power of two that fits them.
+    ///
+    /// This is useful for structures built on top of `FlexArr` that rely on power of two
+    /// capacities, such as hash tables. If the next power of two does not fit into the
+    /// `LengthType`, the capacity saturates at `L::MAX_VALUE` instead.
+    ///
+    /// If the capacity is already sufficient, this method does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity.
+    pub fn reserve_pow2(&mut self, additional: L) -> FlexArrResult<()> {
+        let needed = self.capacity_needed(additional)?;
+        let cap = self.capacity();
+        if cap >= needed {
+            return Ok(());
+        }
+
+        let mut pow2 = L::ONE_VALUE;
+        while pow2 < needed {
+            let Some(next) = pow2.checked_add(pow2) else {
+                pow2 = L::MAX_VALUE;
+                break;
+            };
+            pow2 = next;
+        }
+        return self.inner.expand_capacity_to(pow2, Self::LAYOUT);
+    }
+
     /// Clears all elements from the `FlexArr`, dropping each element without releasing allocated memory.
     ///
     /// This operation resets the array’s length to zero while preserving its capacity.
+    /// For types that do not need dropping, like `u8`, only the length is reset so this is **O(1)**.
     pub fn clear(&mut self) {
-        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+        if Self::NEEDS_DROP {
+            unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+        }
         self.inner.length = L::ZERO_VALUE;
     }
 
+    /// Shrinks the capacity of the `FlexArr` as much as possible, so it matches the length.
+    ///
+    /// The allocator may still keep more memory around than is needed. For zero sized types
+    /// this does nothing since no memory is ever allocated for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The `FlexArr`
+    /// is left untouched in that case.
+    pub fn shrink_to_fit(&mut self) -> FlexArrResult<()> {
+        return self.inner.shrink_capacity_to(self.len(), Self::LAYOUT);
+    }
+
+    /// Shrinks the capacity like `shrink_to_fit()`, but if the allocator fails to shrink the
+    /// memory in place, a new block of the right size is allocated and the elements are
+    /// copied over to it instead.
+    ///
+    /// This helps with allocators that can not shrink a block, but would happily hand out a
+    /// smaller one. The copy needs both blocks at once, so it can use more memory for a moment.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if both shrinking and allocating the smaller block fail.
+    /// The `FlexArr` is left untouched in that case.
+    pub fn shrink_to_fit_copy(&mut self) -> FlexArrResult<()> {
+        return self.inner.shrink_capacity_copy_to(self.len(), Self::LAYOUT);
+    }
+
+    /// Shrinks the capacity of the `FlexArr` down to `min_capacity`, but never below
+    /// the length.
+    ///
+    /// If the capacity is already at most `min_capacity`, this method does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The `FlexArr`
+    /// is left untouched in that case.
+    pub fn shrink_to(&mut self, min_capacity: L) -> FlexArrResult<()> {
+        let capacity = min_capacity.max(self.len());
+        return self.inner.shrink_capacity_to(capacity, Self::LAYOUT);
+    }
+
+    /// Clears all elements from the `FlexArr` like `clear()`, then shrinks the allocation so
+    /// it has a capacity of at most `keep` elements.
+    ///
+    /// If `keep` is zero the memory is released entirely. If the capacity is already at most
+    /// `keep`, the allocation is left as is. This is useful for pooled objects that get reset
+    /// between uses, but should not hold on to an unbounded amount of memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The elements are
+    /// still cleared in that case.
+    pub fn clear_and_shrink(&mut self, keep: L) -> FlexArrResult<()> {
+        self.clear();
+        return self.inner.shrink_capacity_to(keep, Self::LAYOUT);
+    }
+
     /// Reduces the length of the `FlexArr` to the specified value, dropping all elements beyond that point.
     ///
     /// If the provided `length` is greater than or equal to the current length, the method does nothing.
+    ///
+    /// The removed elements are dropped in order starting from `length`. Use `truncate_rev()`
+    /// to drop them starting from the end instead.
     pub fn truncate(&mut self, length: L) {
         let len = self.len();
         if length >= len {
             return;
         }
+        debug_assert!(length < len);
+        if !Self::NEEDS_DROP {
+            // There is nothing to drop, so only the length changes.
+            self.inner.length = length;
+            return;
+        }
         let left_over = (len - length).as_usize();
         let usz = length.as_usize();
 
@@ -164,86 +607,315 @@ where
         self.inner.length = length;
     }
 
-    /// Returns a reference to the current allocator.
-    #[inline]
-    pub const fn allocator(array: &Self) -> &A {
-        return Inner::allocator(&array.inner);
-    }
-}
-
-// Methods for working with individual items.
-impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
-where
-    usize: TryFrom<L>,
-{
-    /// Removes and returns the last element from the `FlexArr`.
+    /// Resizes the `FlexArr` so its length is `new_len`.
     ///
-    /// If the array is empty, this method returns `None`.
-    pub fn pop(&mut self) -> Option<T> {
-        let len = self.inner.length;
-        if len <= L::ZERO_VALUE {
-            return None;
-        }
-        let ret = unsafe { ptr::read(self.as_ptr().add(len.as_usize() - 1)) };
-        self.inner.length = len - L::ONE_VALUE;
-        return Some(ret);
-    }
-
-    /// Appends an element to the end of the `FlexArr`.
+    /// If `new_len` is greater than the current length, the `FlexArr` is extended by clones
+    /// of `value`, with `value` itself moved into the last slot. Otherwise this is the same
+    /// as `truncate()`.
     ///
-    /// If there isn’t enough capacity, this method attempts to expand the underlying storage.
-    /// Should the allocation fail, a `FlexArrErr` is returned.
+    /// The capacity is reserved before any element is cloned, so a `new_len` that can never
+    /// be allocated fails without constructing anything.
     ///
     /// # Errors
     ///
-    /// Returns a `FlexArrErr` if memory expansion fails or if there is a conversion error when
-    /// determining the new index.
-    pub fn push(&mut self, item: T) -> FlexArrResult<()> {
-        let needed = self.capacity_needed(L::ONE_VALUE)?;
-
-        if needed > self.capacity() {
-            self.inner.expand_capacity_at_least(needed, Self::LAYOUT)?;
+    /// Returns a `FlexArrErr` if memory expansion fails, or if `new_len` can not be
+    /// converted to a `usize` or turned into a valid layout.
+    pub fn resize(&mut self, new_len: L, value: T) -> FlexArrResult<()>
+    where
+        T: Clone,
+    {
+        let Some(count) = self.prepare_resize(new_len)? else {
+            return Ok(());
+        };
+        for _ in 1..count {
+            self.push_within_capacity(value.clone());
         }
-
-        let old_len = self.inner.length;
-        // This should always be fine to use `as` since the capacity
-        // should be larger than length. So there is no need to use
-        // try_from() like I was. Since the capacity would have had
-        // to been converted to usize to even allocate the memory.
-        //
-        // In the event the type is a ZST and the length type can
-        // be larger than usize this is also fine, since ANYTHING
-        // added to the dangling pointer for a ZST is going to be
-        // the same Dangling pointer.
-        let usz_len = old_len.as_usize();
-
-        let loc = unsafe { self.as_mut_ptr().add(usz_len) };
-        unsafe { ptr::write(loc, item) };
-
-        // This will always be less or equal to needed so
-        // plain addition is fine.
-        self.inner.length = old_len + L::ONE_VALUE;
-
+        self.push_within_capacity(value);
         return Ok(());
     }
 
-    /// Removes and returns the element at the specified `index` from the `FlexArr`.
+    /// Resizes the `FlexArr` so its length is `new_len`.
     ///
-    /// If the `index` is out of bounds, this method returns `None`.
+    /// If `new_len` is greater than the current length, the `FlexArr` is extended with the
+    /// values returned by calling `f`. Otherwise this is the same as `truncate()`.
     ///
-    /// Note that this operation shifts all elements after `index` one position to the left,
-    /// resulting in **O(n)** time complexity.
+    /// The capacity is reserved before `f` is ever called, so a `new_len` that can never
+    /// be allocated fails without constructing anything.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// - `Some(T)` if the element at `index` was successfully removed.
-    /// - `None` if `index` is out of bounds.
-    pub fn remove(&mut self, index: L) -> Option<T> {
-        let len = self.len();
-        if index >= len {
-            return None;
+    /// Returns a `FlexArrErr` if memory expansion fails, or if `new_len` can not be
+    /// converted to a `usize` or turned into a valid layout.
+    pub fn resize_with<F>(&mut self, new_len: L, mut f: F) -> FlexArrResult<()>
+    where
+        F: FnMut() -> T,
+    {
+        let Some(count) = self.prepare_resize(new_len)? else {
+            return Ok(());
+        };
+        for _ in 0..count {
+            self.push_within_capacity(f());
         }
-
+        return Ok(());
+    }
+
+    /// Keeps only the last `n` elements, dropping the oldest ones at the front and shifting
+    /// the rest down. This is handy for a rolling buffer of the most recent entries.
+    ///
+    /// If the length is already `n` or less, the method does nothing. The capacity is not
+    /// changed. Moving the kept elements to the front is O(n).
+    ///
+    /// If a drop panics, the remaining front elements are still dropped and the kept
+    /// elements are still shifted down.
+    pub fn keep_last(&mut self, n: L) {
+        struct ShiftDownOnDrop<'a, T, A: AltAllocator, L: LengthType>
+        where
+            usize: TryFrom<L>,
+        {
+            removed: usize,
+            kept:    L,
+            arr:     &'a mut FlexArr<T, A, L>,
+        }
+
+        impl<T, A: AltAllocator, L: LengthType> Drop for ShiftDownOnDrop<'_, T, A, L>
+        where
+            usize: TryFrom<L>,
+        {
+            fn drop(&mut self) {
+                let ptr = self.arr.as_mut_ptr();
+                unsafe { ptr::copy(ptr.add(self.removed), ptr, self.kept.as_usize()) };
+                self.arr.inner.length = self.kept;
+            }
+        }
+
+        let len = self.len();
+        if len <= n {
+            return;
+        }
+        if Self::is_zst() {
+            // Zero sized values are all the same, so the kept ones do not have to move and this
+            // is just `truncate()`. The length may not fit in a usize, so drop in parts that do.
+            self.inner.length = n;
+            if !Self::NEEDS_DROP {
+                return;
+            }
+            let mut removed = len - n;
+            while removed > L::ZERO_VALUE {
+                let part = usize::try_from(removed).unwrap_or(usize::MAX);
+                removed = removed - L::usize_as_self(part);
+                unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), part)) };
+            }
+            return;
+        }
+        let removed = (len - n).as_usize();
+        // No element is owned by the array until the guard puts the kept ones back.
+        self.inner.length = L::ZERO_VALUE;
+        let guard = ShiftDownOnDrop {
+            removed: removed,
+            kept:    n,
+            arr:     self,
+        };
+        let slc = unsafe { slice::from_raw_parts_mut(guard.arr.as_mut_ptr(), removed) };
+        unsafe { ptr::drop_in_place(slc) };
+    }
+
+    /// Reduces the length of the `FlexArr` to the specified value like `truncate()`, but the
+    /// removed elements are dropped in reverse order, starting with the last element.
+    ///
+    /// This is useful for types whose drops depend on the ones after them being dropped
+    /// first, such as guards that were acquired in order. If a drop panics, the elements
+    /// that have not been dropped yet are still part of the `FlexArr`.
+    pub fn truncate_rev(&mut self, length: L) {
+        let ptr = self.as_mut_ptr();
+        while self.inner.length > length {
+            let last = self.inner.length - L::ONE_VALUE;
+            self.inner.length = last;
+            unsafe { ptr::drop_in_place(ptr.add(last.as_usize())) };
+        }
+    }
+
+    /// Reduces the length of the `FlexArr` to the specified value without dropping the
+    /// removed elements.
+    ///
+    /// This is useful when the elements are dropped some other way, such as by an arena, or
+    /// when they have nothing to drop. Otherwise their resources are leaked. Leaking is not
+    /// unsafe, but it is easy to do by accident, so prefer `truncate()` unless this is what
+    /// is wanted.
+    ///
+    /// If the provided `length` is greater than or equal to the current length, the method does nothing.
+    #[inline]
+    pub fn truncate_no_drop(&mut self, length: L) {
+        if length < self.inner.length {
+            self.inner.length = length;
+        }
+    }
+
+    /// Removes all elements from the `FlexArr` without dropping them, keeping the capacity.
+    ///
+    /// The same caveats as `truncate_no_drop()` apply.
+    #[inline]
+    pub fn clear_no_drop(&mut self) {
+        self.inner.length = L::ZERO_VALUE;
+    }
+
+    /// Reduces the length like `truncate()`, and then if the new length is below
+    /// `1 / 2^AUTO_SHRINK_SHIFT` of the capacity also calls `shrink_to_fit()`.
+    ///
+    /// This way large buffers that are cut down a lot do not hold onto memory they
+    /// no longer need, while small truncations do not cause reallocations.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The elements
+    /// are still truncated in that case.
+    pub fn truncate_and_maybe_shrink(&mut self, length: L) -> FlexArrResult<()> {
+        self.truncate(length);
+        let threshold = self.capacity() >> L::from(Self::AUTO_SHRINK_SHIFT);
+        if self.len() >= threshold {
+            return Ok(());
+        }
+        return self.shrink_to_fit();
+    }
+
+    /// Returns a reference to the current allocator.
+    ///
+    /// This is an associated function so it can never shadow a method of the same name on
+    /// the elements through `Deref`. The `allocator_ref()` method does the same thing.
+    #[inline]
+    pub const fn allocator(array: &Self) -> &A {
+        return Inner::allocator(&array.inner);
+    }
+
+    /// Returns a reference to the current allocator.
+    ///
+    /// This is the same as `FlexArr::allocator(&array)`, but can be called as a method.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std_alloc")]
+    /// # {
+    /// use flex_array::FlexArr;
+    ///
+    /// let arr = FlexArr::<u32>::new();
+    /// let other = FlexArr::<u8>::new_in(*arr.allocator_ref());
+    /// # let _ = other;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn allocator_ref(&self) -> &A {
+        return Inner::allocator(&self.inner);
+    }
+}
+
+// Methods for working with individual items.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Removes and returns the last element from the `FlexArr`.
+    ///
+    /// If the array is empty, this method returns `None`.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.inner.length;
+        if len <= L::ZERO_VALUE {
+            return None;
+        }
+        let ret = unsafe { ptr::read(self.as_ptr().add(len.as_usize() - 1)) };
+        self.inner.length = len - L::ONE_VALUE;
+        return Some(ret);
+    }
+
+    /// Appends an element to the end of the `FlexArr`.
+    ///
+    /// If there isn’t enough capacity, this method attempts to expand the underlying storage.
+    /// Should the allocation fail, a `FlexArrErr` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if there is a conversion error when
+    /// determining the new index.
+    pub fn push(&mut self, item: T) -> FlexArrResult<()> {
+        let needed = self.capacity_needed(L::ONE_VALUE)?;
+
+        self.grow_amortized(needed)?;
+
+        let old_len = self.inner.length;
+        // This should always be fine to use `as` since the capacity
+        // should be larger than length. So there is no need to use
+        // try_from() like I was. Since the capacity would have had
+        // to been converted to usize to even allocate the memory.
+        //
+        // In the event the type is a ZST and the length type can
+        // be larger than usize this is also fine, since ANYTHING
+        // added to the dangling pointer for a ZST is going to be
+        // the same Dangling pointer.
+        let usz_len = old_len.as_usize();
+
+        let loc = unsafe { self.as_mut_ptr().add(usz_len) };
+        unsafe { ptr::write(loc, item) };
+
+        // This will always be less or equal to needed so
+        // plain addition is fine.
+        self.inner.length = old_len + L::ONE_VALUE;
+
+        return Ok(());
+    }
+
+    /// Appends an element to the end of the `FlexArr` just like `push()`, but returns the
+    /// index the element was placed at.
+    ///
+    /// This is handy for index based structures, like the node list of a graph.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or the length would overflow.
+    #[inline]
+    pub fn push_get_index(&mut self, item: T) -> FlexArrResult<L> {
+        let index = self.inner.length;
+        self.push(item)?;
+        return Ok(index);
+    }
+
+    /// Pushes elements created by `f` until the length reaches the capacity. `f` is called
+    /// with the index each new element will be at.
+    ///
+    /// This never allocates, so it can not fail. It is handy for completely initializing a
+    /// buffer that was set up with `reserve()` or `with_capacity()`.
+    ///
+    /// For zero sized types the capacity is the maximum value of the `LengthType`, so `f`
+    /// is called that many times.
+    pub fn fill_to_capacity<F>(&mut self, mut f: F)
+    where
+        F: FnMut(L) -> T,
+    {
+        let cap = self.capacity();
+        while self.inner.length < cap {
+            let item = f(self.inner.length);
+            self.push_within_capacity(item);
+        }
+    }
+
+    /// Removes and returns the element at the specified `index` from the `FlexArr`.
+    ///
+    /// If the `index` is out of bounds, this method returns `None`.
+    ///
+    /// Note that this operation shifts all elements after `index` one position to the left,
+    /// resulting in **O(n)** time complexity.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(T)` if the element at `index` was successfully removed.
+    /// - `None` if `index` is out of bounds.
+    pub fn remove(&mut self, index: L) -> Option<T> {
+        let len = self.len();
+        if index >= len {
+            return None;
+        }
+        if Self::is_zst() {
+            return Some(self.remove_zst());
+        }
+
+        // Only zero sized types can have a length that does not fit into a usize.
+        debug_assert!(usize::try_from(len).is_ok());
         let usz_len = len.as_usize();
         let usz_ind = index.as_usize();
         let items = usz_len - usz_ind - 1;
@@ -271,8 +943,13 @@ where
         if index >= self.len() {
             return None;
         }
+        if Self::is_zst() {
+            return Some(self.remove_zst());
+        }
 
         // if the check above succeeded then there is always at least one element.
+        // Only zero sized types can have a length that does not fit into a usize.
+        debug_assert!(usize::try_from(self.len()).is_ok());
         let ptr = self.as_mut_ptr();
         let loc = unsafe { ptr.add(index.as_usize()) };
         let end = unsafe { ptr.add(self.len().as_usize() - 1) } as *const T;
@@ -283,12 +960,21 @@ where
         return Some(item);
     }
 
+    /// Every slot of a zero sized type is the same, so removing any of them is just
+    /// taking one off the end. The length can be wider than a usize here, so the index
+    /// is never converted. The caller must make sure the array is not empty.
+    #[inline]
+    fn remove_zst(&mut self) -> T {
+        self.inner.length = self.len() - L::ONE_VALUE;
+        return unsafe { ptr::read(self.as_ptr()) };
+    }
+
     /// Returns a reference to the element at the specified `index`,
     /// or `None` if the index is out of bounds.
     ///
     /// Note that this method only supports single-element access, not
-    /// ranges. Extending to range-based access would require a custom
-    /// trait since Rust's `SliceIndex` trait is sealed
+    /// ranges. Since Rust's `SliceIndex` trait is sealed, use `get_range()`
+    /// for range-based access instead.
     pub fn get(&self, index: L) -> Option<&T> {
         let len = self.len();
         if index >= len {
@@ -319,8 +1005,8 @@ where
     /// or `None` if the index is out of bounds.
     ///
     /// Note that this method only supports single-element access, not
-    /// ranges. Extending to range-based access would require a custom
-    /// trait since Rust's `SliceIndex` trait is sealed
+    /// ranges. Since Rust's `SliceIndex` trait is sealed, use `get_range_mut()`
+    /// for range-based access instead.
     pub fn get_mut(&mut self, index: L) -> Option<&mut T> {
         let len = self.len();
         if index >= len {
@@ -347,6 +1033,107 @@ where
         return refr;
     }
 
+    /// Returns a reference to the first element, or `None` if the `FlexArr` is empty.
+    #[inline]
+    pub fn first(&self) -> Option<&T> {
+        return self.as_slice().first();
+    }
+
+    /// Returns a mutable reference to the first element, or `None` if the `FlexArr` is empty.
+    #[inline]
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        return self.as_mut_slice().first_mut();
+    }
+
+    /// Returns a reference to the last element, or `None` if the `FlexArr` is empty.
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        return self.as_slice().last();
+    }
+
+    /// Returns a mutable reference to the last element, or `None` if the `FlexArr` is empty.
+    #[inline]
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        return self.as_mut_slice().last_mut();
+    }
+
+    /// Returns mutable references to the elements at all the `indices` at once,
+    /// or `None` if any index is out of bounds or two of the indices are the same.
+    ///
+    /// The indices are compared pairwise, so this is **O(N²)** which is fine for
+    /// the handful of indices this is meant for.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [L; N]) -> Option<[&mut T; N]> {
+        if !self.disjoint_in_bounds(&indices) {
+            return None;
+        }
+        return Some(unsafe { self.get_disjoint_unchecked_mut(indices) });
+    }
+
+    /// Returns mutable references to the elements at all the `indices` at once
+    /// without checking the bounds or that the indices are distinct.
+    ///
+    /// This behaves like `get_disjoint_mut()`, but skips the checks, which is useful
+    /// for hot loops where the indices are already known to be valid.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure every index is within bounds, and that no two indices
+    /// are the same. Otherwise this creates aliasing mutable references.
+    #[inline]
+    pub unsafe fn get_disjoint_unchecked_mut<const N: usize>(&mut self, indices: [L; N]) -> [&mut T; N] {
+        debug_assert!(self.disjoint_in_bounds(&indices));
+        let ptr = self.as_mut_ptr();
+        return indices.map(|index| unsafe { &mut *ptr.add(index.as_usize()) });
+    }
+
+    fn disjoint_in_bounds(&self, indices: &[L]) -> bool {
+        let len = self.len();
+        for (i, &index) in indices.iter().enumerate() {
+            if index >= len || indices[..i].contains(&index) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// Returns a reference to the element at the specified `index`.
+    ///
+    /// This is the fallible version of indexing with `[]`. The same conditions that
+    /// would make `[]` panic are returned as errors instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of `IndexOutOfBounds` if `index >= len`, or
+    /// `UsizeOverflow` if the index can not be converted to a `usize`.
+    pub fn try_index(&self, index: L) -> FlexArrResult<&T> {
+        if index >= self.len() {
+            return Err(FlexArrErr::new(ErrorReason::IndexOutOfBounds));
+        }
+        let Ok(i) = usize::try_from(index) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        return Ok(&self.as_slice()[i]);
+    }
+
+    /// Returns a mutable reference to the element at the specified `index`.
+    ///
+    /// This is the fallible version of indexing with `[]`. The same conditions that
+    /// would make `[]` panic are returned as errors instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of `IndexOutOfBounds` if `index >= len`, or
+    /// `UsizeOverflow` if the index can not be converted to a `usize`.
+    pub fn try_index_mut(&mut self, index: L) -> FlexArrResult<&mut T> {
+        if index >= self.len() {
+            return Err(FlexArrErr::new(ErrorReason::IndexOutOfBounds));
+        }
+        let Ok(i) = usize::try_from(index) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        return Ok(&mut self.as_mut_slice()[i]);
+    }
+
     /// Inserts an element at the specified `index`. If the index is out of bounds, an error
     /// is returned.
     ///
@@ -360,18 +1147,34 @@ where
     ///
     /// Additionally, can return `FlexArrErr` with a reason of `IndexOutOfBounds` if the index is out of bounds.
     pub fn insert(&mut self, index: L, item: T) -> FlexArrResult<()> {
+        return self.try_insert(index, item).map_err(|(err, _)| err);
+    }
+
+    /// The same as `insert()`, but if the item can not be inserted it is handed back
+    /// along with the error instead of being dropped.
+    ///
+    /// This is useful for items that are expensive to rebuild, or that must not be
+    /// dropped just because there was no memory to store them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `insert()`, each paired with the `item` that was
+    /// not inserted. The `FlexArr` is left untouched in that case.
+    pub fn try_insert(&mut self, index: L, item: T) -> Result<(), (FlexArrErr, T)> {
         let len = self.inner.length.as_usize();
         let Ok(index) = usize::try_from(index) else {
-            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+            return Err((FlexArrErr::new(ErrorReason::UsizeOverflow), item));
         };
 
         if index > len {
-            return Err(FlexArrErr::new(ErrorReason::IndexOutOfBounds));
+            return Err((FlexArrErr::new(ErrorReason::IndexOutOfBounds), item));
         }
 
-        let needed = self.capacity_needed(L::ONE_VALUE)?;
-        if needed > self.capacity() {
-            self.inner.expand_capacity_at_least(needed, Self::LAYOUT)?;
+        let grown = self
+            .capacity_needed(L::ONE_VALUE)
+            .and_then(|needed| self.grow_amortized(needed));
+        if let Err(err) = grown {
+            return Err((err, item));
         }
 
         // Shift all the elements over one to insert the item.
@@ -384,49 +1187,414 @@ where
         self.inner.length = self.inner.length + L::ONE_VALUE;
         return Ok(());
     }
-}
 
-// Methods for working with or getting slices.
-impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
-where
-    usize: TryFrom<L>,
-{
-    /// Appends a slice of `T` elements to the end of the `FlexArr`.
+    /// Inserts all the items from `iter` starting at the specified `index`, keeping their order.
     ///
-    /// This method is available for types that implement `Copy`. It reserves any necessary
-    /// additional capacity and then copies the elements from the provided slice into the array.
+    /// The iterator's exact length is used to reserve space and shift the elements after
+    /// `index` over only once. If the iterator turns out to yield fewer items than it reported,
+    /// the gap is closed, and any extra items past the reported length are left unused.
     ///
-    /*/// If the type `T` does not implement `Copy`, consider using `extend_from_slice_clone`.*/
+    /// If the iterator panics, the items that were already written are kept and the
+    /// `FlexArr` is left in a valid state.
     ///
     /// # Errors
     ///
-    /// Returns a `FlexArrErr` if memory expansion fails or if there is an error converting
-    /// the capacity or length.
-    pub fn extend_from_slice(&mut self, slice: &[T]) -> FlexArrResult<()>
+    /// Returns a `FlexArrErr` if memory expansion fails, the length would overflow, or
+    /// if there is a conversion error when determining the new index.
+    ///
+    /// Additionally, can return `FlexArrErr` with a reason of `IndexOutOfBounds` if the index is out of bounds.
+    pub fn insert_many<I>(&mut self, index: L, iter: I) -> FlexArrResult<()>
     where
-        T: Copy,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
     {
-        let slc_len = slice.len();
-        self.reserve_usize(slc_len)?;
-
-        let usz_len = self.inner.length.as_usize();
-        let ptr = unsafe { self.as_mut_ptr().add(usz_len) };
-        unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), ptr, slc_len) };
-
-        self.inner.length = L::usize_as_self(slc_len + usz_len);
-        return Ok(());
-    }
-    /*
-        Comment this out for now since while a type that implements Clone may
-        not always allocate memory, if it does there is no way to get the
-        status of the allocation failure. Perhaps a different trait that users
-        can implement.
-
-        pub fn extend_from_slice_clone(&mut self, slice: &[T]) -> FlexArrResult<()>
+        struct CloseGapOnDrop<'a, T, A: AltAllocator, L: LengthType>
         where
-            T: Clone,
+            usize: TryFrom<L>,
         {
-            let slc_len = slice.len();
+            index:   usize,
+            written: usize,
+            count:   usize,
+            tail:    usize,
+            arr:     &'a mut FlexArr<T, A, L>,
+        }
+
+        impl<T, A: AltAllocator, L: LengthType> Drop for CloseGapOnDrop<'_, T, A, L>
+        where
+            usize: TryFrom<L>,
+        {
+            fn drop(&mut self) {
+                let ptr = self.arr.as_mut_ptr();
+                let end = self.index + self.written;
+                if self.written < self.count {
+                    unsafe { ptr::copy(ptr.add(self.index + self.count), ptr.add(end), self.tail) };
+                }
+                self.arr.inner.length = L::usize_as_self(end + self.tail);
+            }
+        }
+
+        let len = self.inner.length.as_usize();
+        let Ok(index) = usize::try_from(index) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+
+        if index > len {
+            return Err(FlexArrErr::new(ErrorReason::IndexOutOfBounds));
+        }
+
+        let mut iter = iter.into_iter();
+        let count = iter.len();
+        let Ok(count_l) = L::try_from(count) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        let needed = self.capacity_needed(count_l)?;
+        self.grow_amortized(needed)?;
+
+        // Move the tail out of the way once to make a gap for all the items.
+        let ptr = self.as_mut_ptr();
+        let tail = len - index;
+        unsafe { ptr::copy(ptr.add(index), ptr.add(index + count), tail) };
+
+        let mut guard = CloseGapOnDrop {
+            index:   index,
+            written: 0,
+            count:   count,
+            tail:    tail,
+            arr:     self,
+        };
+        while guard.written < count {
+            let Some(item) = iter.next() else {
+                break;
+            };
+            unsafe { ptr::write(ptr.add(index + guard.written), item) };
+            guard.written += 1;
+        }
+        return Ok(());
+    }
+
+    /// Inserts an element at the front of the `FlexArr`.
+    ///
+    /// `FlexArr` does not keep a separate head offset, that way its layout stays as small
+    /// as possible and its parts stay compatible with `from_parts()`. So this shifts all
+    /// the elements over by one in a single move, which is **O(n)**. Memory is only
+    /// reallocated when the capacity is exhausted, so alternating `push_front()` and
+    /// `pop_front()` calls will not reallocate. Both ends share the same spare room, so
+    /// there is no separate `reserve_front()`, just use `reserve()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or the length would overflow.
+    #[inline]
+    pub fn push_front(&mut self, item: T) -> FlexArrResult<()> {
+        return self.insert(L::ZERO_VALUE, item);
+    }
+
+    /// Removes and returns the first element from the `FlexArr`.
+    ///
+    /// If the array is empty, this method returns `None`. Like `push_front()` the
+    /// remaining elements are shifted over in a single move, which is **O(n)**.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        return self.remove(L::ZERO_VALUE);
+    }
+
+    /// An alias of `pop_front()`, for code that reads better naming the end it removes from.
+    ///
+    /// Removes and returns the first element, or `None` if the `FlexArr` is empty. The
+    /// remaining elements are shifted over which is **O(n)**.
+    #[inline]
+    pub fn remove_first(&mut self) -> Option<T> {
+        return self.pop_front();
+    }
+
+    /// An alias of `pop()`, the counterpart of `remove_first()`.
+    ///
+    /// Removes and returns the last element, or `None` if the `FlexArr` is empty. Nothing
+    /// needs to be moved so it is **O(1)**.
+    #[inline]
+    pub fn remove_last(&mut self) -> Option<T> {
+        return self.pop();
+    }
+
+    /// Consumes the `FlexArr` and splits off the first element, returning it along with
+    /// the rest of the elements. Returns `None` if the `FlexArr` is empty.
+    ///
+    /// The memory is reused for the rest of the elements, but they are shifted over which
+    /// is **O(n)**. This is handy for recursion that passes ownership along.
+    #[inline]
+    pub fn split_first_owned(mut self) -> Option<(T, Self)> {
+        let first = self.pop_front()?;
+        return Some((first, self));
+    }
+
+    /// Consumes the `FlexArr` and splits off the last element, returning it along with
+    /// the rest of the elements. Returns `None` if the `FlexArr` is empty.
+    ///
+    /// Nothing needs to be moved so this is **O(1)**.
+    #[inline]
+    pub fn split_last_owned(mut self) -> Option<(T, Self)> {
+        let last = self.pop()?;
+        return Some((last, self));
+    }
+}
+
+// Methods for working with or getting slices.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Appends a slice of `T` elements to the end of the `FlexArr`.
+    ///
+    /// This method is available for types that implement `Copy`. It reserves any necessary
+    /// additional capacity and then copies the elements from the provided slice into the array.
+    ///
+    /// When nothing has been allocated yet, exactly enough room for the slice is allocated.
+    /// Later calls grow the capacity using the amortized strategy.
+    ///
+    /*/// If the type `T` does not implement `Copy`, consider using `extend_from_slice_clone`.*/
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if there is an error converting
+    /// the capacity or length.
+    pub fn extend_from_slice(&mut self, slice: &[T]) -> FlexArrResult<()>
+    where
+        T: Copy,
+    {
+        let slc_len = slice.len();
+        let Ok(additional) = L::try_from(slc_len) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        // Work out the new length in the length type up front, so it is
+        // checked once and is exactly what gets stored after the copy.
+        let new_len = self.capacity_needed(additional)?;
+        if self.capacity() == L::ZERO_VALUE {
+            // Building an array once from a slice is common, so the first allocation
+            // is sized to fit the slice exactly instead of using the growth strategy.
+            self.inner.expand_capacity_to(new_len, Self::LAYOUT)?;
+        } else {
+            self.grow_amortized(new_len)?;
+        }
+
+        let usz_len = self.inner.length.as_usize();
+        let ptr = unsafe { self.as_mut_ptr().add(usz_len) };
+        unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), ptr, slc_len) };
+
+        self.inner.length = new_len;
+        return Ok(());
+    }
+
+    /// Appends as much of `slice` as fits in the current capacity, and returns how many
+    /// elements were copied. This never allocates, which makes it suitable for real-time
+    /// code that fills a bounded buffer.
+    pub fn extend_from_slice_within_capacity(&mut self, slice: &[T]) -> L
+    where
+        T: Copy,
+    {
+        let room = self.capacity() - self.len();
+        let room = usize::try_from(room).unwrap_or(usize::MAX);
+        let count = slice.len().min(room);
+
+        let usz_len = self.inner.length.as_usize();
+        let ptr = unsafe { self.as_mut_ptr().add(usz_len) };
+        unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), ptr, count) };
+
+        // No more than the free capacity was copied so this fits in `L`.
+        let count = L::usize_as_self(count);
+        self.inner.length = self.inner.length + count;
+        return count;
+    }
+
+    /// Reserves room for at least `additional` more elements and hands that uninitialized
+    /// space to `f` to fill in. `f` returns how many elements at the start of the space it
+    /// initialized, and the length is advanced by that much.
+    ///
+    /// This is the safe way to bulk initialize elements, such as reading bytes into the
+    /// array straight from I/O, without needing to zero the memory first or call `set_len()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory reallocation fails or if there is an error converting
+    /// the required capacity. `f` is not called in that case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a count larger than `additional`. Any elements `f` initialized
+    /// are leaked in that case.
+    pub fn reserve_and_fill<F>(&mut self, additional: L, f: F) -> FlexArrResult<()>
+    where
+        F: FnOnce(&mut [MaybeUninit<T>]) -> L,
+    {
+        self.reserve(additional)?;
+        let Ok(usz_add) = usize::try_from(additional) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+
+        let usz_len = self.inner.length.as_usize();
+        // Safety: reserve() made sure there is room for `additional` elements past the length.
+        let spare = unsafe {
+            let ptr = self.as_mut_ptr().add(usz_len).cast::<MaybeUninit<T>>();
+            slice::from_raw_parts_mut(ptr, usz_add)
+        };
+
+        let filled = f(spare);
+        assert!(
+            filled <= additional,
+            "reserve_and_fill() was told more elements were filled than reserved"
+        );
+        // This can not overflow since the reserve above succeeded.
+        self.inner.length = self.inner.length + filled;
+        return Ok(());
+    }
+
+    /// Truncates the `FlexArr` to `length` elements, returning the removed tail in a new
+    /// `FlexArr` instead of dropping it. This is the inverse of `append()`.
+    ///
+    /// The returned `FlexArr` uses a clone of this array's allocator, with exactly enough
+    /// memory for the tail. If `length` is at least the current length an empty `FlexArr`
+    /// is returned without allocating.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the memory for the tail could not be allocated.
+    /// The `FlexArr` is left untouched in that case.
+    pub fn split_off_tail(&mut self, length: L) -> FlexArrResult<FlexArr<T, A, L>>
+    where
+        A: Clone,
+    {
+        let len = self.len();
+        if length >= len {
+            return self.new_buffer(L::ZERO_VALUE);
+        }
+
+        let count = len - length;
+        let mut tail = self.new_buffer(count)?;
+        let src = unsafe { self.as_ptr().add(length.as_usize()) };
+        unsafe { ptr::copy_nonoverlapping(src, tail.as_mut_ptr(), count.as_usize()) };
+        tail.inner.length = count;
+        self.inner.length = length;
+        return Ok(tail);
+    }
+
+    /// Appends the elements of anything that can be viewed as a slice, such as an array,
+    /// a `Vec`, or another `FlexArr`.
+    ///
+    /// This is the same as `extend_from_slice(src.as_ref())`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if there is an error converting
+    /// the capacity or length.
+    #[inline]
+    pub fn extend_from<S: AsRef<[T]>>(&mut self, src: S) -> FlexArrResult<()>
+    where
+        T: Copy,
+    {
+        return self.extend_from_slice(src.as_ref());
+    }
+
+    /// Moves all the elements of `other` to the end of the `FlexArr`, leaving `other` empty.
+    ///
+    /// If this `FlexArr` is empty and has nothing allocated, the two are just swapped, so the
+    /// elements are never copied and nothing is allocated. In that case this `FlexArr` takes
+    /// over `other`'s memory along with its allocator, and `other` is left with this one's
+    /// allocator and no memory. Otherwise `other` keeps its capacity.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if the length would overflow.
+    /// Both arrays are left untouched in that case.
+    pub fn append(&mut self, other: &mut Self) -> FlexArrResult<()> {
+        if self.inner.length == L::ZERO_VALUE && self.inner.capacity == L::ZERO_VALUE {
+            mem::swap(&mut self.inner, &mut other.inner);
+            return Ok(());
+        }
+
+        let count = other.inner.length;
+        self.reserve(count)?;
+        let len = self.len().as_usize();
+        let dst = unsafe { self.as_mut_ptr().add(len) };
+        unsafe { ptr::copy_nonoverlapping(other.as_ptr(), dst, count.as_usize()) };
+
+        other.inner.length = L::ZERO_VALUE;
+        self.inner.length = self.inner.length + count;
+        return Ok(());
+    }
+
+    /// Appends all the items from `iter` to the end of the `FlexArr`.
+    ///
+    /// The lower bound of the iterator's size hint is reserved up front using the amortized
+    /// growth strategy, so an iterator with an exact length needs at most one allocation.
+    /// Any items past the lower bound grow the capacity as needed while pushing the rest.
+    /// Since the growth is amortized, extending many times with a few items at a time does
+    /// not reallocate on every call.
+    ///
+    /// Iterators like `filter()` often have a lower bound of zero. Then nothing is reserved
+    /// up front, and each push grows the capacity using the amortized strategy, so the number
+    /// of allocations still only grows logarithmically with the number of items.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if the length would overflow.
+    /// The items added before the error are kept.
+    pub fn try_extend<I>(&mut self, iter: I) -> FlexArrResult<()>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let Ok(lower) = L::try_from(lower) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        self.reserve(lower)?;
+
+        for item in iter {
+            self.push(item)?;
+        }
+        return Ok(());
+    }
+
+    /// Appends the items from `iter` that are not already in the `FlexArr`, which is handy
+    /// for set like accumulation into small arrays.
+    ///
+    /// Every item is compared against all the elements in the array, including those added
+    /// earlier by this call, so this is **O(n·m)**. For large arrays a real set such as a
+    /// hash set is a much better choice.
+    ///
+    /// Only the lower bound of the iterator's size hint is reserved up front, since any of
+    /// the items could turn out to be duplicates.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory expansion fails or if the length would overflow.
+    /// The items added before the error are kept.
+    pub fn extend_unique<I>(&mut self, iter: I) -> FlexArrResult<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: PartialEq,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.reserve_usize(lower)?;
+        for item in iter {
+            if self.as_slice().contains(&item) {
+                continue;
+            }
+            self.push(item)?;
+        }
+        return Ok(());
+    }
+    /*
+        Comment this out for now since while a type that implements Clone may
+        not always allocate memory, if it does there is no way to get the
+        status of the allocation failure. Perhaps a different trait that users
+        can implement.
+
+        pub fn extend_from_slice_clone(&mut self, slice: &[T]) -> FlexArrResult<()>
+        where
+            T: Clone,
+        {
+            let slc_len = slice.len();
             self.expand_by_slice_len(slc_len)?;
 
             let usz_len = self.inner.length.as_usize();
@@ -451,6 +1619,7 @@ where
     /// on it will be a `usize`.
     #[inline]
     pub fn as_slice(&self) -> &[T] {
+        debug_assert!(self.len() <= self.capacity());
         unsafe { slice::from_raw_parts(self.as_ptr(), self.inner.length.as_usize()) }
     }
 
@@ -459,156 +1628,1271 @@ where
     /// on it will be a `usize`.
     #[inline]
     pub fn as_mut_slice(&mut self) -> &mut [T] {
+        debug_assert!(self.len() <= self.capacity());
         unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.inner.length.as_usize()) }
     }
-}
 
-// Pretty much attribute methods and constants.
-impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
-where
-    usize: TryFrom<L>,
-{
-    const LAYOUT: Layout = Layout::new::<T>();
-    const SIZE: usize = size_of::<T>();
+    /// Returns an iterator that yields copies of the elements, without consuming the `FlexArr`.
+    ///
+    /// This is the same as `iter().copied()`, which is handy for numeric arrays.
+    #[inline]
+    pub fn iter_copied(&self) -> Copied<slice::Iter<'_, T>>
+    where
+        T: Copy,
+    {
+        return self.as_slice().iter().copied();
+    }
 
-    /// Determines if the `FlexArr` is empty.
+    /// Splits the elements into arrays of `N` elements starting from the front, along with
+    /// the remainder that is shorter than `N`.
+    ///
+    /// This is handy for SIMD friendly processing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        return self.len() == L::ZERO_VALUE;
+    pub fn as_chunks<const N: usize>(&self) -> (&[[T; N]], &[T]) {
+        let (chunks, rem) = self.as_slice().split_at(Self::chunked_len::<N>(self.len_usize()));
+        // Safety: `chunks` holds a multiple of `N` elements, and `[T; N]` is laid out as `N` `T`s in a row.
+        let chunks = unsafe { slice::from_raw_parts(chunks.as_ptr().cast(), chunks.len() / N) };
+        return (chunks, rem);
+    }
+
+    /// Splits the elements into arrays of `N` elements starting from the back, along with
+    /// the remainder at the front that is shorter than `N`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[inline]
+    pub fn as_rchunks<const N: usize>(&self) -> (&[T], &[[T; N]]) {
+        let len = self.len_usize();
+        let (rem, chunks) = self.as_slice().split_at(len - Self::chunked_len::<N>(len));
+        // Safety: `chunks` holds a multiple of `N` elements, and `[T; N]` is laid out as `N` `T`s in a row.
+        let chunks = unsafe { slice::from_raw_parts(chunks.as_ptr().cast(), chunks.len() / N) };
+        return (rem, chunks);
+    }
+
+    /// Consumes the `FlexArr` and returns an iterator that copies each element out.
+    ///
+    /// This is like `into_iter()`, but since `Copy` types never need dropping the iterator
+    /// does not have to keep track of what is left to drop. The memory is given back once
+    /// the iterator is dropped.
+    pub fn into_iter_copied(self) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + FusedIterator
+    where
+        T: Copy,
+    {
+        let len = self.len_usize();
+        // Safety: The range never goes past the length, and the closure owns the array
+        // so the memory stays valid while the iterator is alive.
+        return (0..len).map(move |i| unsafe { *self.as_slice().get_unchecked(i) });
+    }
+
+    /// Consumes the `FlexArr` and returns an iterator that moves the elements out in arrays
+    /// of `N` elements, starting from the back. The elements at the front that do not fill
+    /// a whole chunk are left over, see `IntoRChunks::remainder()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[inline]
+    pub fn into_rchunks<const N: usize>(self) -> IntoRChunks<T, A, L, N> {
+        return IntoRChunks::new(self);
+    }
+
+    /// Splits the elements into mutable arrays of `N` elements starting from the front,
+    /// along with the remainder that is shorter than `N`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[inline]
+    pub fn as_chunks_mut<const N: usize>(&mut self) -> (&mut [[T; N]], &mut [T]) {
+        let mid = Self::chunked_len::<N>(self.len_usize());
+        let (chunks, rem) = self.as_mut_slice().split_at_mut(mid);
+        // Safety: `chunks` holds a multiple of `N` elements, and `[T; N]` is laid out as `N` `T`s in a row.
+        let chunks = unsafe { slice::from_raw_parts_mut(chunks.as_mut_ptr().cast(), chunks.len() / N) };
+        return (chunks, rem);
+    }
+
+    /// Returns a `FlexSlice` view of the elements in `range`, or `None` if the range
+    /// is out of bounds.
+    ///
+    /// The range bounds use the `LengthType`, and any range that is inverted, goes past
+    /// the length, or has a bound that can not be converted to a `usize` returns `None`
+    /// rather than being truncated.
+    pub fn get_range<R: FlexIndex<L>>(&self, range: R) -> Option<&FlexSlice<T, L>> {
+        let range = range.to_range(self.len())?;
+        let slc = self.as_slice().get(range)?;
+        return Some(FlexSlice::from_slice(slc));
+    }
+
+    /// Returns a mutable `FlexSlice` view of the elements in `range`, or `None` if the
+    /// range is out of bounds.
+    ///
+    /// The range is checked the same way as `get_range()`.
+    pub fn get_range_mut<R: FlexIndex<L>>(&mut self, range: R) -> Option<&mut FlexSlice<T, L>> {
+        let range = range.to_range(self.len())?;
+        let slc = self.as_mut_slice().get_mut(range)?;
+        return Some(FlexSlice::from_mut_slice(slc));
+    }
+
+    /// Returns a `FlexSlice` view of the elements in `range` without performing any bounds checking.
+    ///
+    /// This method behaves like `get_range()`, but skips the checks, which can help in hot loops
+    /// where the range is already known to be valid.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `range.start <= range.end` and `range.end <= self.len()`.
+    /// Anything else results in undefined behavior.
+    #[inline]
+    pub unsafe fn get_range_unchecked(&self, range: Range<L>) -> &FlexSlice<T, L> {
+        debug_assert!(range.start <= range.end && range.end <= self.len());
+        let start = range.start.as_usize();
+        let count = (range.end - range.start).as_usize();
+        let slc = unsafe { slice::from_raw_parts(self.as_ptr().add(start), count) };
+        return FlexSlice::from_slice(slc);
+    }
+
+    /// Returns a mutable `FlexSlice` view of the elements in `range` without performing any
+    /// bounds checking.
+    ///
+    /// This method behaves like `get_range_mut()`, but skips the checks. The view borrows the
+    /// whole `FlexArr` mutably, so no other reference into it can exist while the view does.
+    /// To work on several disjoint ranges at once split the view with `split_at_mut()`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `range.start <= range.end` and `range.end <= self.len()`.
+    /// Anything else results in undefined behavior.
+    #[inline]
+    pub unsafe fn get_range_mut_unchecked(&mut self, range: Range<L>) -> &mut FlexSlice<T, L> {
+        debug_assert!(range.start <= range.end && range.end <= self.len());
+        let start = range.start.as_usize();
+        let count = (range.end - range.start).as_usize();
+        let slc = unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(start), count) };
+        return FlexSlice::from_mut_slice(slc);
+    }
+
+    /// Divides the `FlexArr` into two mutable `FlexSlice` views at `mid`. The first holds the
+    /// elements before `mid` and the second holds the rest, so both can be worked on at once
+    /// while indexing with `L`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length.
+    pub fn split_at_mut(&mut self, mid: L) -> (&mut FlexSlice<T, L>, &mut FlexSlice<T, L>) {
+        let len = self.len();
+        if mid > len {
+            panic!("Split index is out of bounds for a length of {}", len.as_usize());
+        }
+        let (front, back) = self.as_mut_slice().split_at_mut(mid.as_usize());
+        return (FlexSlice::from_mut_slice(front), FlexSlice::from_mut_slice(back));
+    }
+
+    /// Determines if this `FlexArr` has the same elements as `other`, even if `other`
+    /// uses a different allocator or `LengthType`.
+    ///
+    /// This is the same as using `==`, but can make the intent clearer, for example when
+    /// comparing data that was serialized and deserialized with different length types.
+    #[inline]
+    pub fn eq_contents<A2, L2>(&self, other: &FlexArr<T, A2, L2>) -> bool
+    where
+        T: PartialEq,
+        A2: AltAllocator,
+        L2: LengthType,
+        usize: TryFrom<L2>,
+    {
+        return self.as_slice() == other.as_slice();
+    }
+
+    /// Returns an iterator over all contiguous windows of length `size`. The windows
+    /// overlap and are yielded as `FlexSlice` views so the indexing stays in `L`.
+    ///
+    /// If the `FlexArr` is shorter than `size`, the iterator yields nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn windows(&self, size: L) -> Windows<'_, T, L> {
+        // If the size does not fit into a usize no window can ever be that long.
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        return Windows::new(self.as_slice().windows(size));
+    }
+
+    /// Returns an iterator over `size` elements of the `FlexArr` at a time. The chunks
+    /// do not overlap and are yielded as `FlexSlice` views so the indexing stays in `L`.
+    ///
+    /// If the length is not evenly divided by `size`, the last chunk will be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn chunks(&self, size: L) -> Chunks<'_, T, L> {
+        let size = usize::try_from(size).unwrap_or(usize::MAX);
+        return Chunks::new(self.as_slice().chunks(size));
+    }
+}
+
+// Methods for removing multiple elements.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Removes the elements in `range` from the `FlexArr`, returning an iterator that moves
+    /// them out. The iterator can take elements from the front or the back.
+    ///
+    /// When the iterator is dropped any of the elements it did not yield are dropped, and the
+    /// elements after the range are moved down to close the gap. The memory is kept.
+    ///
+    /// If the iterator is leaked, for example with `mem::forget()`, the `FlexArr` may lose
+    /// the drained elements and the ones after them, but nothing is dropped twice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or inverted.
+    pub fn drain<R: FlexIndex<L>>(&mut self, range: R) -> Drain<'_, T, A, L> {
+        let Some(rng) = range.to_range(self.len()) else {
+            panic!("Range is out of bounds for a length of {}", self.len().as_usize());
+        };
+        return Drain::new(self, rng.start, rng.end);
+    }
+
+    /// Retains only the elements for which `f` returns `true`, removing the rest.
+    ///
+    /// The elements are visited in order, and the order of the retained elements is preserved.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_with(|item| f(item), drop);
+    }
+
+    /// Retains only the elements for which `f` returns `true` just like `retain()`,
+    /// but `f` is given a mutable reference to each element.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.retain_with(f, drop);
+    }
+
+    /// Retains only the elements for which `f` returns `true` like `retain()`, and then
+    /// shrinks the memory to fit the elements that are left.
+    ///
+    /// This is handy for long lived buffers that were filtered down a lot, so they do
+    /// not hold onto memory they no longer need.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The elements
+    /// are still removed in that case.
+    pub fn retain_and_shrink<F>(&mut self, f: F) -> FlexArrResult<()>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        return self.retain_compact(f, CompactPolicy::Always);
+    }
+
+    /// Retains only the elements for which `f` returns `true` like `retain()`, and then
+    /// shrinks the memory to fit the elements that are left depending on `policy`.
+    ///
+    /// Shrinking can mean the allocator has to move the elements, so this allows trading
+    /// memory for time. For example `CompactPolicy::WhenBelowShift(2)` only gives
+    /// memory back when less than a quarter of it is in use.
+    ///
+    /// The policy only looks at the length and capacity. `AltAllocator` has no way to
+    /// report if shrinking is cheap, so how costly a shrink is for the allocator is not
+    /// taken into account.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The elements
+    /// are still removed in that case.
+    pub fn retain_compact<F>(&mut self, f: F, policy: CompactPolicy) -> FlexArrResult<()>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain(f);
+        let shrink = match policy {
+            CompactPolicy::Never => false,
+            CompactPolicy::Always => true,
+            CompactPolicy::WhenBelowShift(shift) => {
+                // Shifting by the bit width or more would overflow, there is nothing below that.
+                let threshold = self.capacity_usize().checked_shr(u32::from(shift)).unwrap_or(0);
+                self.len_usize() < threshold
+            },
+        };
+        if !shrink {
+            return Ok(());
+        }
+        return self.shrink_to_fit();
+    }
+
+    /// Retains only the elements for which `f` returns `true`, and returns a new `FlexArr`
+    /// containing the removed elements in their original order.
+    ///
+    /// The returned `FlexArr` uses a clone of this array's allocator. Space for every element
+    /// is reserved before anything is removed, so once the call starts removing elements it
+    /// can no longer fail. The returned array is then shrunk to fit if the allocator allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the memory for the removed elements could not be allocated.
+    /// The `FlexArr` is left untouched in that case.
+    pub fn retain_and_collect<F>(&mut self, mut f: F) -> FlexArrResult<FlexArr<T, A, L>>
+    where
+        F: FnMut(&T) -> bool,
+        A: Clone,
+    {
+        let mut removed = self.new_buffer(self.len())?;
+        self.retain_with(
+            |item| f(item),
+            |item| {
+                let ret = removed.push(item);
+                // There is always room since the whole length was reserved.
+                debug_assert!(ret.is_ok());
+            },
+        );
+        // Not being able to give back the extra memory is not an error.
+        let _ = removed.shrink_to_fit();
+        return Ok(removed);
+    }
+
+    /// Consumes the `FlexArr` and splits its elements into two arrays depending on `f`.
+    ///
+    /// The elements `f` returns `true` for stay in this array's buffer, and are returned first.
+    /// The rest are moved into a new `FlexArr` using `alloc_b`. Both keep the elements in
+    /// their original order. Space for every element is reserved in the second array up front,
+    /// which is then shrunk to fit if the allocator allows.
+    ///
+    /// If `f` panics, both arrays are dropped without any element being dropped twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the memory for the second array could not be allocated.
+    /// Since the `FlexArr` was consumed, its elements are dropped in that case.
+    pub fn partition_in<F>(mut self, mut f: F, alloc_b: A) -> FlexArrResult<(Self, FlexArr<T, A, L>)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut other = FlexArr::with_capacity_in(alloc_b, self.len())?;
+        self.retain_with(
+            |item| f(item),
+            |item| {
+                let ret = other.push(item);
+                // There is always room since the whole length was reserved.
+                debug_assert!(ret.is_ok());
+            },
+        );
+        let _ = other.shrink_to_fit();
+        return Ok((self, other));
+    }
+
+    /// Removes consecutive repeated elements from the `FlexArr`.
+    ///
+    /// If the `FlexArr` is sorted, this removes all duplicates.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes consecutive repeated elements like `dedup()`, but keeps the last element
+    /// of each run instead of the first.
+    ///
+    /// The values left over compare the same either way, this only matters when equal
+    /// elements can still be told apart, like records where the newest should win.
+    pub fn dedup_keep_last(&mut self)
+    where
+        T: PartialEq,
+    {
+        // Swapping the newer duplicate into the kept spot means the older one is what
+        // gets removed.
+        self.dedup_with(
+            |a, b| {
+                if a != b {
+                    return false;
+                }
+                mem::swap(a, b);
+                return true;
+            },
+            drop,
+        );
+    }
+
+    /// Removes consecutive repeated elements just like `dedup()`, but returns how many
+    /// elements were removed.
+    pub fn dedup_count(&mut self) -> L
+    where
+        T: PartialEq,
+    {
+        return self.dedup_with(|a, b| a == b, drop);
+    }
+
+    /// Removes all but the first of consecutive elements that resolve to the same key.
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive elements that satisfy the given equality
+    /// relation.
+    ///
+    /// The `same` closure is passed references to two elements and must determine if they
+    /// compare equal. The elements are passed in the opposite order from their order in the
+    /// `FlexArr`, so if `same(a, b)` returns `true`, `a` is removed.
+    pub fn dedup_by<F>(&mut self, same: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.dedup_with(same, drop);
+    }
+
+    /// Removes consecutive elements that satisfy the given equality relation just like
+    /// `dedup_by()`, but the removed elements are returned in a new `FlexArr` in their
+    /// original order instead of being dropped.
+    ///
+    /// This is useful when the duplicates still carry information, for example when
+    /// merging records. The returned `FlexArr` uses a clone of this array's allocator.
+    /// Space for every element is reserved before anything is removed, so once the call
+    /// starts removing elements it can no longer fail. The returned array is then shrunk
+    /// to fit if the allocator allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the memory for the removed elements could not be allocated.
+    /// The `FlexArr` is left untouched in that case.
+    pub fn dedup_extract<F>(&mut self, same: F) -> FlexArrResult<FlexArr<T, A, L>>
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+        A: Clone,
+    {
+        let mut removed = self.new_buffer(self.len())?;
+        self.dedup_with(same, |item| removed.push_within_capacity(item));
+        // Not being able to give back the extra memory is not an error.
+        let _ = removed.shrink_to_fit();
+        return Ok(removed);
+    }
+}
+
+// Methods for sorting and reordering.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Checks if the elements of the `FlexArr` are sorted in ascending order.
+    ///
+    /// This is handy to skip a sort that is not needed.
+    #[inline]
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        return self.as_slice().is_sorted();
+    }
+
+    /// Checks if the elements of the `FlexArr` are sorted using the given comparison function.
+    ///
+    /// `compare` is called on each pair of neighbouring elements and must return `true`
+    /// when they are in order.
+    #[inline]
+    pub fn is_sorted_by<F>(&self, compare: F) -> bool
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        return self.as_slice().is_sorted_by(compare);
+    }
+
+    /// Reverses the order of the elements in the `FlexArr` in place.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+
+    /// Reverses the order of only the elements in `range`, leaving the rest in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or inverted.
+    pub fn reverse_range<R: FlexIndex<L>>(&mut self, range: R) {
+        let Some(slc) = self.get_range_mut(range) else {
+            panic!("Range is out of bounds for a length of {}", self.len().as_usize());
+        };
+        slc.reverse();
+    }
+
+    /// Sorts the `FlexArr`. The sort is stable.
+    ///
+    /// This is a merge sort like `sort()` on slices, but the scratch buffer it needs is
+    /// allocated fallibly using a clone of the array's allocator. So instead of panicking or
+    /// aborting if the memory cannot be allocated, an error is returned and the `FlexArr` is
+    /// left untouched. Short arrays are sorted without allocating at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the scratch buffer could not be allocated.
+    #[inline]
+    pub fn try_sort(&mut self) -> FlexArrResult<()>
+    where
+        T: Ord,
+        A: Clone,
+    {
+        return self.try_sort_by(T::cmp);
+    }
+
+    /// Sorts the `FlexArr` with a comparison function. The sort is stable.
+    ///
+    /// This works the same as `try_sort()`. If `compare` panics, all the elements are
+    /// still in the `FlexArr`, but their order is unspecified.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the scratch buffer could not be allocated.
+    pub fn try_sort_by<F>(&mut self, mut compare: F) -> FlexArrResult<()>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        A: Clone,
+    {
+        // Every value of a zero sized type is the same, so there is nothing to reorder.
+        if Self::is_zst() {
+            return Ok(());
+        }
+        let len = self.len().as_usize();
+        let scratch = sort::scratch_len(len);
+        // Half of the length always fits in the length type, and reserving
+        // nothing does not touch the allocator.
+        let mut buf = FlexArr::<MaybeUninit<T>, A, L>::new_in(self.inner.alloc.clone());
+        buf.reserve_exact(L::usize_as_self(scratch))?;
+        let buf = buf.as_mut_ptr().cast::<T>();
+
+        let mut is_less = |a: &T, b: &T| compare(a, b) == Ordering::Less;
+        // The buffer has room for `scratch` elements and only ever holds bitwise
+        // copies. Its length stays zero so it never drops any of them.
+        unsafe { sort::merge_sort(self.as_mut_slice(), buf, &mut is_less) };
+        return Ok(());
+    }
+
+    /// Sorts the `FlexArr` with a key extraction function. The sort is stable.
+    ///
+    /// This is `try_sort_by()` comparing the keys, so the key is computed again every time
+    /// two elements are compared. That is the better choice when the keys are cheap to
+    /// compute, since no memory is needed for them. If the keys are expensive, such as
+    /// building a `String`, use `try_sort_by_cached_key()` which computes each key once but
+    /// has to store them all.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the scratch buffer could not be allocated.
+    /// The `FlexArr` is left untouched in that case.
+    pub fn try_sort_by_key<K, F>(&mut self, mut f: F) -> FlexArrResult<()>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+        A: Clone,
+    {
+        return self.try_sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Sorts the `FlexArr` with a key extraction function, calling the function only once
+    /// per element. The sort is stable.
+    ///
+    /// This behaves like `sort_by_cached_key()` on slices, except the buffer used to cache
+    /// the keys is allocated fallibly using a clone of the array's allocator. So instead of
+    /// panicking or aborting if the memory cannot be allocated, an error is returned and the
+    /// `FlexArr` is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the buffer for the cached keys could not be allocated.
+    pub fn try_sort_by_cached_key<K, F>(&mut self, mut f: F) -> FlexArrResult<()>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+        A: Clone,
+    {
+        let len = self.len();
+        if len <= L::ONE_VALUE {
+            return Ok(());
+        }
+
+        let mut keys = FlexArr::<(K, L), A, L>::with_capacity_in(self.inner.alloc.clone(), len)?;
+        let mut i = L::ZERO_VALUE;
+        for item in self.as_slice() {
+            // The capacity was reserved above so this can not fail.
+            keys.push((f(item), i))?;
+            i = i + L::ONE_VALUE;
+        }
+
+        // Each index is unique so ties on the key are broken by the original position,
+        // which makes the unstable sort here give a stable result overall.
+        keys.as_mut_slice().sort_unstable();
+
+        // Apply the permutation in place. An element that was already swapped away
+        // is found by following where it was moved to.
+        let keys = keys.as_mut_slice();
+        for i in 0..keys.len() {
+            let mut index = keys[i].1.as_usize();
+            while index < i {
+                index = keys[index].1.as_usize();
+            }
+            keys[i].1 = L::usize_as_self(index);
+            self.as_mut_slice().swap(i, index);
+        }
+        return Ok(());
+    }
+}
+
+// Pretty much attribute methods and constants.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    const LAYOUT: Layout = Layout::new::<T>();
+    const SIZE: usize = size_of::<T>();
+    const NEEDS_DROP: bool = mem::needs_drop::<T>();
+    // Safety: A size of zero is always valid for the alignment of a type.
+    const EMPTY_LAYOUT: Layout = unsafe { Layout::from_size_align_unchecked(0, Self::LAYOUT.align()) };
+
+    /// Used by `truncate_and_maybe_shrink()`. When the length drops below
+    /// `capacity >> AUTO_SHRINK_SHIFT` (a quarter of the capacity) the
+    /// memory is shrunk to fit.
+    pub const AUTO_SHRINK_SHIFT: u8 = 2;
+
+    /// The most times `reserve_with_retry()` will retry after the first failed attempt.
+    pub const RESERVE_RETRY_LIMIT: u8 = 4;
+
+    /// The most elements the `Debug` output prints. Any past this are summarized
+    /// with the total count, so printing a huge `FlexArr` stays usable.
+    pub const DEBUG_ELEMENT_LIMIT: usize = 128;
+
+    /// Determines if `T` is a zero sized type. This is known at compile time, so any
+    /// branch on it is optimized away.
+    ///
+    /// A `FlexArr` of a zero sized type never allocates, and its capacity is always
+    /// the maximum value of the `LengthType`.
+    #[inline(always)]
+    pub const fn is_zst() -> bool {
+        return size_is_zst(Self::SIZE);
+    }
+
+    /// Determines if the `FlexArr` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        return self.len() == L::ZERO_VALUE;
+    }
+
+    /// Returns the number of elements in the `FlexArr`.
+    #[inline]
+    pub const fn len(&self) -> L {
+        return self.inner.length;
+    }
+
+    /// Returns the number of elements `FlexArr` can store without needing to reallocate.
+    ///
+    /// This can be more than what was requested when the allocator returned a larger block of
+    /// memory than asked for, since any extra whole elements are adopted into the capacity.
+    ///
+    /// For zero sized types, this function will return the maximum value for the `LengthType`.
+    pub const fn capacity(&self) -> L {
+        return self.inner.capacity(Self::SIZE);
+    }
+
+    /// Returns the number of elements in the `FlexArr` as a `usize`.
+    ///
+    /// This is handy when working with slices and other APIs that use `usize`. The
+    /// length always fits in a `usize`, since it was checked when the elements were added.
+    #[inline]
+    pub fn len_usize(&self) -> usize {
+        return self.len().as_usize();
+    }
+
+    /// Returns the capacity of the `FlexArr` as a `usize`.
+    ///
+    /// For zero sized types the capacity is the maximum value of the `LengthType`, which
+    /// can be larger than `usize::MAX` for wide length types. In that case this saturates
+    /// at `usize::MAX`.
+    #[inline]
+    pub fn capacity_usize(&self) -> usize {
+        return usize::try_from(self.capacity()).unwrap_or(usize::MAX);
+    }
+
+    /// Returns how many more elements the `LengthType` allows before the length would
+    /// overflow, which is `L::MAX_VALUE - len()`.
+    ///
+    /// This ignores memory completely, unlike `capacity() - len()` which is how many more
+    /// elements fit in what is already allocated. It is handy when a small length type
+    /// like `u8` is used on purpose as the limit of a bounded buffer.
+    #[inline]
+    pub fn remaining_capacity_headroom(&self) -> L {
+        return L::MAX_VALUE - self.len();
+    }
+
+    /// Returns a raw pointer to the underlying storage. If the type is zero sized
+    /// the pointer value will be a dangling pointer. Like one would get with
+    /// `NonNull::dangling()` ect...
+    ///
+    /// # Safety
+    /// The caller should ensure the underlying storage outlives this pointer.
+    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
+    #[inline]
+    pub const fn as_ptr(&self) -> *const T {
+        return self.inner.get_ptr();
+    }
+
+    /// Returns a raw mutable pointer to the underlying storage. If the type is zero sized
+    /// the pointer value will be a dangling pointer. Like one would get with
+    /// `NonNull::dangling()` ect...
+    ///
+    /// # Safety
+    /// The caller should ensure the underlying storage outlives this pointer.
+    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
+    #[inline]
+    pub const fn as_mut_ptr(&mut self) -> *mut T {
+        return self.inner.get_mut_ptr();
+    }
+
+    /// Returns a `NonNull` pointer to the underlying storage. If the type is zero sized
+    /// the pointer value will be a dangling pointer. Like one would get with
+    /// `NonNull::dangling()` ect...
+    ///
+    /// # Safety
+    /// The caller should ensure the underlying storage outlives this pointer.
+    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
+    #[inline]
+    pub const fn as_non_null(&mut self) -> NonNull<T> {
+        return self.inner.get_non_null();
+    }
+
+    /// Consumes the `FlexArr` and returns a `NonNull` pointer to the underlying memory.
+    ///
+    /// Unlike `into_parts()`, this method only returns the pointer; it does not return
+    /// the length, capacity, or allocator. This is mainly useful if you are already tracking
+    /// those separately.
+    ///
+    /// After calling this method, you are responsible for managing the memory. If you need
+    /// to properly deallocate it and avoid leaks, you should reconstruct a `FlexArr` using
+    /// `from_parts()`.
+    #[inline]
+    pub const fn into_non_null(mut self) -> NonNull<T> {
+        let ptr = self.inner.get_non_null();
+        forget(self);
+        return ptr;
+    }
+
+    /// Constructs a `FlexArr` from its raw components: a pointer, length, capacity, and allocator.
+    ///
+    /// # Safety
+    ///
+    /// This function has quite a few safety requirements that must be upheld:
+    ///
+    /// - `ptr`
+    ///   - Must point to a memory block allocated by `alloc`.
+    ///   - The total size in bytes must not exceed `isize::MAX`.
+    /// - `T`
+    ///   - The layout of `T` must match the layout used when allocating `ptr`.
+    /// - `length`
+    ///   - Must be ≤ `capacity`.
+    ///   - Must not exceed the number of properly initialized elements in `ptr`.
+    /// - `capacity`
+    ///   - Must match the number of elements the original allocation can hold (i.e., the layout used).
+    ///
+    /// Violating any of these requirements results like will result in undefined behavior
+    #[inline]
+    pub const unsafe fn from_parts(ptr: NonNull<T>, length: L, capacity: L, alloc: A) -> Self {
+        return Self {
+            inner: Inner {
+                ptr:      ptr.cast(),
+                length:   length,
+                capacity: capacity,
+                alloc:    alloc,
+            },
+            _ph:   PhantomData,
+        };
+    }
+
+    /// Constructs a `FlexArr` from its raw components like `from_parts()`, but first checks
+    /// the parts that can be checked.
+    ///
+    /// This catches arithmetic mistakes, such as a length larger than the capacity or a
+    /// capacity whose size in bytes could never have been allocated.
+    ///
+    /// # Safety
+    ///
+    /// The pointer itself can not be checked, so the requirements of `from_parts()` about
+    /// `ptr` still must be upheld. It must point to a memory block allocated by `alloc` that
+    /// holds `capacity` elements, the first `length` of which are initialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of:
+    /// - `CapacityOverflow` if `length` is greater than `capacity`.
+    /// - `UsizeOverflow` if the capacity or its size in bytes does not fit into a `usize`.
+    /// - `LayoutFailure` if the size in bytes is over `isize::MAX` or `ptr` is not aligned for `T`.
+    pub unsafe fn try_from_parts(ptr: NonNull<T>, length: L, capacity: L, alloc: A) -> FlexArrResult<Self> {
+        if length > capacity {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        }
+        let Ok(usz_cap) = usize::try_from(capacity) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        if !ptr.is_aligned() {
+            return Err(FlexArrErr::new(ErrorReason::LayoutFailure));
+        }
+        // The layout can only be created if the size is at most `isize::MAX`.
+        if !Self::is_zst() {
+            layout_array(Self::LAYOUT, usz_cap)?;
+        }
+        return Ok(unsafe { Self::from_parts(ptr, length, capacity, alloc) });
+    }
+
+    /// Constructs an empty `FlexArr` over a block of memory that was already allocated by `alloc`,
+    /// such as memory handed out by a fixed pool.
+    ///
+    /// Unlike `from_parts()` the capacity is not passed in. It is worked out from the byte
+    /// length of `buf`, so it always matches the real size of the block. Any trailing bytes
+    /// that can not hold a whole element are left unused.
+    ///
+    /// # Safety
+    ///
+    /// - `buf` must be a memory block currently allocated by `alloc`.
+    /// - The layout `buf` was allocated with must have the same alignment as `T`.
+    /// - The size of that layout must be at most the byte length of `buf` rounded down to a
+    ///   multiple of the size of `T`. Requesting a multiple of the size of `T` always satisfies this.
+    /// - On success the `FlexArr` takes ownership of the block and will deallocate it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of `LayoutFailure` if `T` is a zero sized type or
+    /// `buf` can not hold a single element, or `CapacityOverflow` if the capacity does not fit
+    /// into the `LengthType`. The caller still owns `buf` in either case.
+    pub unsafe fn with_exact_capacity_from_layout(buf: NonNull<[u8]>, alloc: A) -> FlexArrResult<Self> {
+        let elements = if Self::is_zst() {
+            0
+        } else {
+            buf.len() / Self::LAYOUT.pad_to_align().size()
+        };
+        if elements == 0 {
+            return Err(FlexArrErr::new(ErrorReason::LayoutFailure));
+        }
+        let Ok(capacity) = L::try_from(elements) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        return Ok(unsafe { Self::from_parts(buf.cast(), L::ZERO_VALUE, capacity, alloc) });
+    }
+
+    /// Consumes the `FlexArr` and returns its raw components as a tuple:
+    ///
+    /// - `NonNull<T>`: A pointer to the underlying memory.
+    /// - `L`: The length of the `FlexArr`.
+    /// - `L`: The capacity of the `FlexArr`.
+    /// - `A`: The allocator used to allocate the memory.
+    ///
+    /// After calling this method, you are responsible for managing the memory. If you need
+    /// to properly deallocate it and avoid leaks, you should reconstruct a `FlexArr` using
+    /// `from_parts()`.
+    #[inline]
+    pub const fn into_parts(mut self) -> (NonNull<T>, L, L, A) {
+        let ptr: NonNull<T> = self.inner.get_non_null();
+        let len = self.inner.length;
+        let cap = self.capacity();
+
+        let self_ptr = &mut self as *mut Self;
+        let alloc_ptr = unsafe { &mut (*self_ptr).inner.alloc as *mut A };
+        let alloc = unsafe { alloc_ptr.read() };
+
+        forget(self);
+        return (ptr, len, cap, alloc);
+    }
+
+    /// Consumes the `FlexArr` and returns its raw components just like `into_parts()`, but
+    /// with the `Layout` the memory is currently allocated with added on the end.
+    ///
+    /// This saves having to work out the layout when deallocating the memory by hand, which is
+    /// easy to get wrong for over aligned types. If nothing has been allocated, such as for
+    /// zero sized types, the layout has a size of zero.
+    ///
+    /// The parts can be turned back into a `FlexArr` with `from_parts_with_layout()`.
+    pub fn into_parts_with_layout(self) -> (NonNull<T>, L, L, A, Layout) {
+        let layout = self.inner.current_layout(Self::LAYOUT).unwrap_or(Self::EMPTY_LAYOUT);
+        let (ptr, len, cap, alloc) = self.into_parts();
+        return (ptr, len, cap, alloc, layout);
+    }
+
+    /// Constructs a `FlexArr` from the raw components returned by `into_parts_with_layout()`.
+    ///
+    /// This works like `from_parts()`, except `layout` is checked against the layout a
+    /// `FlexArr` of `T` with the given `capacity` would use.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as `from_parts()` must be upheld.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` with a reason of `LayoutFailure` if `layout` does not match, or
+    /// `UsizeOverflow` if the capacity can not be converted. The caller still owns the memory
+    /// in that case.
+    pub unsafe fn from_parts_with_layout(
+        ptr: NonNull<T>,
+        length: L,
+        capacity: L,
+        alloc: A,
+        layout: Layout,
+    ) -> FlexArrResult<Self> {
+        let Ok(usz_cap) = usize::try_from(capacity) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        let expected = if Self::is_zst() || usz_cap == 0 {
+            Self::EMPTY_LAYOUT
+        } else {
+            layout_array(Self::LAYOUT, usz_cap)?
+        };
+        if layout != expected {
+            return Err(FlexArrErr::new(ErrorReason::LayoutFailure));
+        }
+        return Ok(unsafe { Self::from_parts(ptr, length, capacity, alloc) });
+    }
+}
+
+// Non-public helper methods.
+impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Sets the length without touching any elements. The caller must make sure
+    /// `length <= capacity` and that the first `length` elements are initialized.
+    #[inline]
+    pub(crate) unsafe fn set_len(&mut self, length: L) {
+        debug_assert!(length <= self.capacity());
+        self.inner.length = length;
+    }
+
+    /// The shared implementation for the `retain` family. Every element `keep` returns
+    /// `false` for is moved out and handed to `removed`.
+    fn retain_with<F, R>(&mut self, mut keep: F, mut removed: R)
+    where
+        F: FnMut(&mut T) -> bool,
+        R: FnMut(T),
+    {
+        // If `keep` or `removed` panics the guard shifts the unprocessed elements
+        // down over the removed ones so the array is still valid.
+        struct BackshiftOnDrop<'a, T, A: AltAllocator, L: LengthType>
+        where
+            usize: TryFrom<L>,
+        {
+            processed: usize,
+            deleted:   usize,
+            len:       usize,
+            arr:       &'a mut FlexArr<T, A, L>,
+        }
+
+        impl<T, A: AltAllocator, L: LengthType> Drop for BackshiftOnDrop<'_, T, A, L>
+        where
+            usize: TryFrom<L>,
+        {
+            fn drop(&mut self) {
+                let ptr = self.arr.as_mut_ptr();
+                if self.deleted > 0 {
+                    let left = self.len - self.processed;
+                    unsafe { ptr::copy(ptr.add(self.processed), ptr.add(self.processed - self.deleted), left) };
+                }
+                self.arr.inner.length = L::usize_as_self(self.len - self.deleted);
+            }
+        }
+
+        let len = self.len().as_usize();
+        let ptr = self.as_mut_ptr();
+        let mut guard = BackshiftOnDrop {
+            processed: 0,
+            deleted:   0,
+            len:       len,
+            arr:       self,
+        };
+
+        while guard.processed != len {
+            let cur = unsafe { ptr.add(guard.processed) };
+            if !keep(unsafe { &mut *cur }) {
+                // Account for the element before handing it off, so
+                // if `removed` panics it's not touched again.
+                guard.processed += 1;
+                guard.deleted += 1;
+                removed(unsafe { ptr::read(cur) });
+                continue;
+            }
+            if guard.deleted > 0 {
+                let hole = unsafe { ptr.add(guard.processed - guard.deleted) };
+                unsafe { ptr::copy_nonoverlapping(cur, hole, 1) };
+            }
+            guard.processed += 1;
+        }
+    }
+
+    /// The shared implementation for the `dedup` family. Every duplicate is moved out and
+    /// handed to `removed`. Returns how many elements were removed.
+    fn dedup_with<F, R>(&mut self, mut same: F, mut removed: R) -> L
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+        R: FnMut(T),
+    {
+        let len = self.len().as_usize();
+        if len <= 1 {
+            return L::ZERO_VALUE;
+        }
+
+        let ptr = self.as_mut_ptr();
+        // Skip ahead to the first duplicate, nothing needs moved before it.
+        let mut first_dup = 1;
+        while first_dup != len {
+            let found = unsafe { same(&mut *ptr.add(first_dup), &mut *ptr.add(first_dup - 1)) };
+            if found {
+                break;
+            }
+            first_dup += 1;
+        }
+        if first_dup == len {
+            return L::ZERO_VALUE;
+        }
+
+        // If `same` or `removed` panics the guard moves the unchecked tail down into the
+        // hole so the array is still valid and nothing is dropped twice.
+        struct FillGapOnDrop<'a, T, A: AltAllocator, L: LengthType>
+        where
+            usize: TryFrom<L>,
+        {
+            read:  usize,
+            write: usize,
+            len:   usize,
+            arr:   &'a mut FlexArr<T, A, L>,
+        }
+
+        impl<T, A: AltAllocator, L: LengthType> Drop for FillGapOnDrop<'_, T, A, L>
+        where
+            usize: TryFrom<L>,
+        {
+            fn drop(&mut self) {
+                let ptr = self.arr.as_mut_ptr();
+                let left = self.len - self.read;
+                unsafe { ptr::copy(ptr.add(self.read), ptr.add(self.write), left) };
+                self.arr.inner.length = L::usize_as_self(self.write + left);
+            }
+        }
+
+        let mut gap = FillGapOnDrop {
+            read:  first_dup + 1,
+            write: first_dup,
+            len:   len,
+            arr:   self,
+        };
+        // The read position already starts past the first duplicate, so if
+        // `removed` panics the guard will not touch it again.
+        removed(unsafe { ptr::read(ptr.add(first_dup)) });
+
+        while gap.read < len {
+            let read = unsafe { ptr.add(gap.read) };
+            let prev = unsafe { ptr.add(gap.write - 1) };
+            if unsafe { same(&mut *read, &mut *prev) } {
+                gap.read += 1;
+                removed(unsafe { ptr::read(read) });
+            } else {
+                let write = unsafe { ptr.add(gap.write) };
+                unsafe { ptr::copy_nonoverlapping(read, write, 1) };
+                gap.write += 1;
+                gap.read += 1;
+            }
+        }
+
+        let write = gap.write;
+        gap.arr.inner.length = L::usize_as_self(write);
+        forget(gap);
+        return L::usize_as_self(len - write);
+    }
+
+    /// Makes sure there is room for `needed` elements in total, growing by the amortized
+    /// strategy if not. The current capacity is only looked up once and handed down, so the
+    /// common case of already having room is a single comparison.
+    #[inline(always)]
+    fn grow_amortized(&mut self, needed: L) -> FlexArrResult<()> {
+        let cap = self.capacity();
+        if needed <= cap {
+            return Ok(());
+        }
+        return self.inner.expand_capacity_at_least(cap, needed, Self::LAYOUT);
+    }
+
+    /// Does the shared work for the resize methods. Shrinking is handled here by truncating
+    /// and returns `None`. When growing, the capacity is reserved and the number of elements
+    /// to add is returned.
+    fn prepare_resize(&mut self, new_len: L) -> FlexArrResult<Option<usize>> {
+        let len = self.len();
+        if new_len <= len {
+            self.truncate(new_len);
+            return Ok(None);
+        }
+        // Zero sized types never allocate so this makes sure the length can still
+        // be used for a slice before anything gets constructed.
+        let Ok(usz_len) = usize::try_from(new_len) else {
+            return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
+        };
+        self.grow_amortized(new_len)?;
+        return Ok(Some(usz_len - len.as_usize()));
     }
 
-    /// Returns the number of elements in the `FlexArr`.
+    /// Creates an empty `FlexArr` with exactly room for `capacity` elements,
+    /// using a clone of this array's allocator.
     #[inline]
-    pub const fn len(&self) -> L {
-        return self.inner.length;
+    fn new_buffer(&self, capacity: L) -> FlexArrResult<Self>
+    where
+        A: Clone,
+    {
+        return Ok(Self {
+            inner: self.inner.clone_buffer(capacity, Self::LAYOUT)?,
+            _ph:   PhantomData,
+        });
     }
 
-    /// Returns the number of elements `FlexArr` can store without needing to reallocate.
-    ///
-    /// For zero sized types, this function will return the maximum value for the `LengthType`.
-    pub const fn capacity(&self) -> L {
-        return self.inner.capacity(Self::SIZE);
+    /// Writes `item` past the end and bumps the length. The caller must
+    /// have already made sure there is enough capacity.
+    #[inline(always)]
+    fn push_within_capacity(&mut self, item: T) {
+        let len = self.inner.length;
+        debug_assert!(len < self.capacity());
+        unsafe { ptr::write(self.as_mut_ptr().add(len.as_usize()), item) };
+        self.inner.length = len + L::ONE_VALUE;
     }
 
-    /// Returns a raw pointer to the underlying storage. If the type is zero sized
-    /// the pointer value will be a dangling pointer. Like one would get with
-    /// `NonNull::dangling()` ect...
-    ///
-    /// # Safety
-    /// The caller should ensure the underlying storage outlives this pointer.
-    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
-    #[inline]
-    pub const fn as_ptr(&self) -> *const T {
-        return self.inner.get_ptr();
+    /// How many of `len` elements fill whole chunks of `N`, used by `as_chunks()` and friends.
+    #[inline(always)]
+    fn chunked_len<const N: usize>(len: usize) -> usize {
+        assert!(N != 0, "chunk size must be non-zero");
+        return len - len % N;
     }
 
-    /// Returns a raw mutable pointer to the underlying storage. If the type is zero sized
-    /// the pointer value will be a dangling pointer. Like one would get with
-    /// `NonNull::dangling()` ect...
-    ///
-    /// # Safety
-    /// The caller should ensure the underlying storage outlives this pointer.
-    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
-    #[inline]
-    pub const fn as_mut_ptr(&mut self) -> *mut T {
-        return self.inner.get_mut_ptr();
+    #[inline(always)]
+    fn capacity_needed(&self, requested: L) -> FlexArrResult<L> {
+        let Some(needed) = self.inner.length.checked_add(requested) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        return Ok(needed);
     }
+}
 
-    /// Returns a `NonNull` pointer to the underlying storage. If the type is zero sized
-    /// the pointer value will be a dangling pointer. Like one would get with
-    /// `NonNull::dangling()` ect...
+// Methods for byte buffers.
+impl<A: AltAllocator, L: LengthType> FlexArr<u8, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Creates a new `FlexArr` of `len` zero bytes using the provided allocator.
     ///
-    /// # Safety
-    /// The caller should ensure the underlying storage outlives this pointer.
-    /// Adding/removing items to the `FlexArr` can cause the pointer to become invalid.
-    #[inline]
-    pub const fn as_non_null(&mut self) -> NonNull<T> {
-        return self.inner.get_non_null();
-    }
-
-    /// Consumes the `FlexArr` and returns a `NonNull` pointer to the underlying memory.
+    /// The memory is requested already zeroed from the allocator, so this can be cheaper
+    /// than pushing zeros. This is handy for getting a buffer that is ready to be read into.
     ///
-    /// Unlike `into_parts()`, this method only returns the pointer; it does not return
-    /// the length, capacity, or allocator. This is mainly useful if you are already tracking
-    /// those separately.
+    /// # Errors
     ///
-    /// After calling this method, you are responsible for managing the memory. If you need
-    /// to properly deallocate it and avoid leaks, you should reconstruct a `FlexArr` using
-    /// `from_parts()`.
-    #[inline]
-    pub const fn into_non_null(mut self) -> NonNull<T> {
-        let ptr = self.inner.get_non_null();
-        forget(self);
-        return ptr;
+    /// Returns a `FlexArrErr` if the allocation fails or if there is an error converting
+    /// the length.
+    pub fn zeroed_in(alloc: A, len: L) -> FlexArrResult<Self> {
+        let mut inner = Inner::new_in::<u8>(alloc);
+        if len > L::ZERO_VALUE {
+            inner.expand_capacity_zeroed_to(len, Self::LAYOUT)?;
+        }
+        // Every byte is now initialized to zero which is a valid `u8`.
+        inner.length = len;
+        return Ok(Self {
+            inner: inner,
+            _ph:   PhantomData,
+        });
     }
 
-    /// Constructs a `FlexArr` from its raw components: a pointer, length, capacity, and allocator.
-    ///
-    /// # Safety
+    /// Creates a new `FlexArr` holding all of the `slices` one after another, using the
+    /// provided allocator.
     ///
-    /// This function has quite a few safety requirements that must be upheld:
+    /// The total length is worked out first so the memory is reserved exactly once. This
+    /// avoids repeated growth when assembling something like a framed message from pieces.
+    /// If there are no bytes at all, an empty `FlexArr` is returned without allocating.
     ///
-    /// - `ptr`
-    ///   - Must point to a memory block allocated by `alloc`.
-    ///   - The total size in bytes must not exceed `isize::MAX`.
-    /// - `T`
-    ///   - The layout of `T` must match the layout used when allocating `ptr`.
-    /// - `length`
-    ///   - Must be ≤ `capacity`.
-    ///   - Must not exceed the number of properly initialized elements in `ptr`.
-    /// - `capacity`
-    ///   - Must match the number of elements the original allocation can hold (i.e., the layout used).
+    /// # Errors
     ///
-    /// Violating any of these requirements results like will result in undefined behavior
-    #[inline]
-    pub const unsafe fn from_parts(ptr: NonNull<T>, length: L, capacity: L, alloc: A) -> Self {
-        return Self {
-            inner: Inner {
-                ptr:      ptr.cast(),
-                length:   length,
-                capacity: capacity,
-                alloc:    alloc,
-            },
-            _ph:   PhantomData,
+    /// Returns a `FlexArrErr` if the total length does not fit in the `LengthType` or
+    /// if the allocation fails.
+    pub fn from_slices_in(slices: &[&[u8]], alloc: A) -> FlexArrResult<Self> {
+        let mut total: usize = 0;
+        for slc in slices {
+            let Some(sum) = total.checked_add(slc.len()) else {
+                return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+            };
+            total = sum;
+        }
+        let Ok(total) = L::try_from(total) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
         };
+
+        let mut arr = Self::with_capacity_in(alloc, total)?;
+        for slc in slices {
+            // The capacity was reserved above so this never grows.
+            arr.extend_from_slice(slc)?;
+        }
+        return Ok(arr);
     }
 
-    /// Consumes the `FlexArr` and returns its raw components as a tuple:
-    ///
-    /// - `NonNull<T>`: A pointer to the underlying memory.
-    /// - `L`: The length of the `FlexArr`.
-    /// - `L`: The capacity of the `FlexArr`.
-    /// - `A`: The allocator used to allocate the memory.
+    /// Returns the index of the first byte equal to `needle`, or `None` if it is not found.
     ///
-    /// After calling this method, you are responsible for managing the memory. If you need
-    /// to properly deallocate it and avoid leaks, you should reconstruct a `FlexArr` using
-    /// `from_parts()`.
+    /// With the `memchr` feature enabled this uses the `memchr` crate for a SIMD accelerated
+    /// search, otherwise the bytes are just scanned.
     #[inline]
-    pub const fn into_parts(mut self) -> (NonNull<T>, L, L, A) {
-        let ptr: NonNull<T> = self.inner.get_non_null();
-        let len = self.inner.length;
-        let cap = self.inner.capacity(Self::SIZE);
-
-        let self_ptr = &mut self as *mut Self;
-        let alloc_ptr = unsafe { &mut (*self_ptr).inner.alloc as *mut A };
-        let alloc = unsafe { alloc_ptr.read() };
+    pub fn find_byte(&self, needle: u8) -> Option<L> {
+        #[cfg(feature = "memchr")]
+        let found = memchr::memchr(needle, self.as_slice());
+        #[cfg(not(feature = "memchr"))]
+        let found = self.as_slice().iter().position(|&b| b == needle);
+        // The index is less than the length so it always fits in `L`.
+        return found.map(L::usize_as_self);
+    }
 
-        forget(self);
-        return (ptr, len, cap, alloc);
+    /// Returns the index of the last byte equal to `needle`, or `None` if it is not found.
+    ///
+    /// With the `memchr` feature enabled this uses the `memchr` crate for a SIMD accelerated
+    /// search, otherwise the bytes are just scanned from the back.
+    #[inline]
+    pub fn rfind_byte(&self, needle: u8) -> Option<L> {
+        #[cfg(feature = "memchr")]
+        let found = memchr::memrchr(needle, self.as_slice());
+        #[cfg(not(feature = "memchr"))]
+        let found = self.as_slice().iter().rposition(|&b| b == needle);
+        return found.map(L::usize_as_self);
     }
 }
 
-// Non-public helper methods.
-impl<T, A: AltAllocator, L: LengthType> FlexArr<T, A, L>
+// Conversions for the `allocator-api2` crate.
+#[cfg(feature = "alloc_api2")]
+impl<T, A: Alloc2, L: LengthType> FlexArr<T, A, L>
 where
     usize: TryFrom<L>,
 {
-    #[inline(always)]
-    fn capacity_needed(&self, requested: L) -> FlexArrResult<L> {
-        let Some(needed) = self.inner.length.checked_add(requested) else {
-            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
-        };
-        return Ok(needed);
+    /// Converts the `FlexArr` into an `allocator_api2` boxed slice that uses the same allocator.
+    ///
+    /// The memory is first shrunk to exactly fit the elements, since a boxed slice is
+    /// deallocated using its length. The buffer is then handed over to the `Box` without
+    /// copying any elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if the allocator fails to shrink the memory. The `FlexArr`
+    /// and its elements are dropped in that case.
+    pub fn try_into_api2_box(mut self) -> FlexArrResult<Api2Box<[T], A>> {
+        self.shrink_to_fit()?;
+        let (ptr, len, _, alloc) = self.into_parts();
+        let slc = NonNull::slice_from_raw_parts(ptr, len.as_usize());
+        // Safety: The memory was allocated by `alloc` and the capacity now matches the length,
+        // so the layout of the slice is the layout of the allocation. For zero sized types
+        // and empty arrays the pointer is dangling and well aligned just like a `Box` expects.
+        return Ok(unsafe { Api2Box::from_raw_in(slc.as_ptr(), alloc) });
     }
 }
 
@@ -640,6 +2924,166 @@ where
 
 // Trait implementations.
 
+#[cfg(feature = "std_alloc")]
+impl<T, L: LengthType> Default for FlexArr<T, Global, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Creates an empty `FlexArr` using the standard allocator. The same as `FlexArr::new()`.
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// # Errors
+///
+/// Converting returns a `FlexArrErr` with a reason of `CapacityOverflow` if the length of
+/// the slice does not fit in the `LengthType`, or `AllocFailure` if the memory could not be
+/// allocated. The memory is allocated to exactly fit the slice.
+#[cfg(feature = "std_alloc")]
+impl<T: Copy, L: LengthType> TryFrom<&[T]> for FlexArr<T, Global, L>
+where
+    usize: TryFrom<L>,
+{
+    type Error = FlexArrErr;
+
+    fn try_from(slice: &[T]) -> FlexArrResult<Self> {
+        let Ok(len) = L::try_from(slice.len()) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        let mut arr = Self::with_capacity(len)?;
+        arr.extend_from_slice(slice)?;
+        return Ok(arr);
+    }
+}
+
+/// # Panics
+///
+/// Cloning panics if the memory for the copy can not be allocated.
+/// Use `try_clone()` to handle that case instead.
+impl<T: Clone, A: AltAllocator + Clone, L: LengthType> Clone for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn clone(&self) -> Self {
+        let Ok(arr) = self.try_clone() else {
+            panic!("Failed to allocate memory to clone a FlexArr");
+        };
+        return arr;
+    }
+}
+
+/// # Debug Output
+/// Prints the elements like a slice does, but only up to `DEBUG_ELEMENT_LIMIT` of them.
+/// For longer arrays the rest are replaced by an ellipsis and the total count, for
+/// example `[1, 2, ..., <10000 total>]`.
+impl<T: core::fmt::Debug, A: AltAllocator, L: LengthType> core::fmt::Debug for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct Elided(usize);
+
+        impl core::fmt::Debug for Elided {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                return write!(f, "..., <{} total>", self.0);
+            }
+        }
+
+        let slc = self.as_slice();
+        if slc.len() <= Self::DEBUG_ELEMENT_LIMIT {
+            return slc.fmt(f);
+        }
+        return f
+            .debug_list()
+            .entries(&slc[..Self::DEBUG_ELEMENT_LIMIT])
+            .entry(&Elided(slc.len()))
+            .finish();
+    }
+}
+
+impl<T, U, A1, A2, L1, L2> PartialEq<FlexArr<U, A2, L2>> for FlexArr<T, A1, L1>
+where
+    T: PartialEq<U>,
+    A1: AltAllocator,
+    A2: AltAllocator,
+    L1: LengthType,
+    L2: LengthType,
+    usize: TryFrom<L1>,
+    usize: TryFrom<L2>,
+{
+    /// Compares the elements only, so arrays with different allocators or
+    /// length types are equal when their contents are.
+    #[inline]
+    fn eq(&self, other: &FlexArr<U, A2, L2>) -> bool {
+        return self.as_slice() == other.as_slice();
+    }
+}
+
+impl<T, U, A: AltAllocator, L: LengthType> PartialEq<[U]> for FlexArr<T, A, L>
+where
+    T: PartialEq<U>,
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        return self.as_slice() == other;
+    }
+}
+
+impl<T, U, A: AltAllocator, L: LengthType, const N: usize> PartialEq<[U; N]> for FlexArr<T, A, L>
+where
+    T: PartialEq<U>,
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn eq(&self, other: &[U; N]) -> bool {
+        return self.as_slice() == other;
+    }
+}
+
+impl<T: Eq, A: AltAllocator, L: LengthType> Eq for FlexArr<T, A, L> where usize: TryFrom<L> {}
+
+/// Arrays are compared lexicographically by their elements, the same as `Vec` and slices.
+/// The allocators and `LengthType`s do not have to match.
+impl<T, A1, A2, L1, L2> PartialOrd<FlexArr<T, A2, L2>> for FlexArr<T, A1, L1>
+where
+    T: PartialOrd,
+    A1: AltAllocator,
+    A2: AltAllocator,
+    L1: LengthType,
+    L2: LengthType,
+    usize: TryFrom<L1> + TryFrom<L2>,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &FlexArr<T, A2, L2>) -> Option<Ordering> {
+        return self.as_slice().partial_cmp(other.as_slice());
+    }
+}
+
+/// Arrays are ordered lexicographically by their elements, the same as `Vec` and slices.
+impl<T: Ord, A: AltAllocator, L: LengthType> Ord for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.as_slice().cmp(other.as_slice());
+    }
+}
+
+/// The hash only depends on the elements, and matches the hash of a `Vec` or
+/// slice with the same contents.
+impl<T: Hash, A: AltAllocator, L: LengthType> Hash for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
 /// # Note on Indexing
 /// Just like `[]` on rusts slices, arras and Vec, an `index >= length`
 /// will panic. This can also panic if the index value is too large to
@@ -680,6 +3124,102 @@ where
     }
 }
 
+macro_rules! impl_range_index {
+    ($($range:ty),* $(,)?) => {
+        $(
+            /// # Note on Indexing
+            /// Just like slicing rusts slices, a range that is inverted or goes past
+            /// the length will panic. Unlike `get_range()` the view is returned directly.
+            impl<T, A: AltAllocator, L: LengthType> Index<$range> for FlexArr<T, A, L>
+            where
+                usize: TryFrom<L>,
+            {
+                type Output = FlexSlice<T, L>;
+                #[track_caller]
+                fn index(&self, range: $range) -> &Self::Output {
+                    let len = self.len();
+                    let Some(rng) = range.clone().to_range(len) else {
+                        range_index_fail(&range, len);
+                    };
+                    return FlexSlice::from_slice(&self.as_slice()[rng]);
+                }
+            }
+
+            /// # Note on Indexing
+            /// Just like slicing rusts slices, a range that is inverted or goes past
+            /// the length will panic. Unlike `get_range_mut()` the view is returned directly.
+            impl<T, A: AltAllocator, L: LengthType> IndexMut<$range> for FlexArr<T, A, L>
+            where
+                usize: TryFrom<L>,
+            {
+                #[track_caller]
+                fn index_mut(&mut self, range: $range) -> &mut Self::Output {
+                    let len = self.len();
+                    let Some(rng) = range.clone().to_range(len) else {
+                        range_index_fail(&range, len);
+                    };
+                    return FlexSlice::from_mut_slice(&mut self.as_mut_slice()[rng]);
+                }
+            }
+        )*
+    };
+}
+
+impl_range_index!(
+    Range<L>,
+    RangeFrom<L>,
+    RangeTo<L>,
+    RangeInclusive<L>,
+    RangeToInclusive<L>,
+    RangeFull
+);
+
+// Works out which bound of the range was bad so the panic message can say so.
+#[cold]
+#[track_caller]
+fn range_index_fail<L: LengthType, R: RangeBounds<L>>(range: &R, len: L) -> !
+where
+    usize: TryFrom<L>,
+{
+    let len = len.as_usize();
+    let start = match range.start_bound() {
+        Bound::Included(&start) => Some(start),
+        Bound::Excluded(&start) => start.checked_add(L::ONE_VALUE),
+        Bound::Unbounded => Some(L::ZERO_VALUE),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(L::ONE_VALUE),
+        Bound::Excluded(&end) => Some(end),
+        Bound::Unbounded => Some(L::usize_as_self(len)),
+    };
+    let (Some(start), Some(end)) = (start, end) else {
+        panic!("Range bound overflows the length type, the length is {len}");
+    };
+    let Ok(usz_end) = usize::try_from(end) else {
+        panic!("Range end index cannot be converted to usize, the length is {len}");
+    };
+    if usz_end > len {
+        panic!("Range end index {usz_end} is out of bounds for a length of {len}");
+    }
+    // The end is in bounds so the start must be past it.
+    let Ok(usz_start) = usize::try_from(start) else {
+        panic!("Range start index cannot be converted to usize, the length is {len}");
+    };
+    panic!("Range start index {usz_start} is after the end index {usz_end}, the length is {len}");
+}
+
+/// # Send and Sync
+/// The `NonNull` pointer stored internally would otherwise make `FlexArr` neither
+/// `Send` nor `Sync`. However, just like `Vec`, a `FlexArr` uniquely owns its buffer
+/// and elements. So sending it to another thread is sound as long as the elements and
+/// the allocator can be sent too.
+unsafe impl<T: Send, A: AltAllocator + Send, L: LengthType + Send> Send for FlexArr<T, A, L> where usize: TryFrom<L> {}
+
+/// # Send and Sync
+/// Sharing a `&FlexArr` only gives shared access to the elements and the allocator, so
+/// it is `Sync` when they both are, which again matches `Vec`.
+unsafe impl<T: Sync, A: AltAllocator + Sync, L: LengthType + Sync> Sync for FlexArr<T, A, L> where usize: TryFrom<L> {}
+
 impl<T, A: AltAllocator, L: LengthType> Drop for FlexArr<T, A, L>
 where
     usize: TryFrom<L>,
@@ -714,6 +3254,37 @@ where
     }
 }
 
+impl<T, A: AltAllocator, L: LengthType> AsRef<[T]> for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn as_ref(&self) -> &[T] {
+        return self.as_slice();
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> AsMut<[T]> for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn as_mut(&mut self) -> &mut [T] {
+        return self.as_mut_slice();
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> IntoIterator for FlexArr<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, A, L>;
+
+    /// Consumes the `FlexArr` and returns an iterator that moves each element out.
+    fn into_iter(self) -> Self::IntoIter {
+        return IntoIter::new(self);
+    }
+}
+
 impl<'a, T, A: AltAllocator, L: LengthType> IntoIterator for &'a FlexArr<T, A, L>
 where
     usize: TryFrom<L>,
@@ -1,4 +1,5 @@
 use core::alloc::Layout;
+use core::ptr;
 use core::ptr::NonNull;
 
 use crate::alloc::AltAllocator;
@@ -9,7 +10,7 @@ use crate::types::FlexArrErr;
 use crate::types::FlexArrResult;
 use crate::types::LengthType;
 
-const fn layout_array(layout: Layout, length: usize) -> FlexArrResult<Layout> {
+pub(crate) const fn layout_array(layout: Layout, length: usize) -> FlexArrResult<Layout> {
     let lay = layout.pad_to_align();
     let Some(len) = length.checked_mul(lay.size()) else {
         return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
@@ -20,6 +21,13 @@ const fn layout_array(layout: Layout, length: usize) -> FlexArrResult<Layout> {
     return Ok(lay);
 }
 
+/// Determines if a type of `size` bytes is zero sized. This is what `FlexArr::is_zst()`
+/// uses too, so the zero sized type branches all agree.
+#[inline(always)]
+pub(crate) const fn size_is_zst(size: usize) -> bool {
+    return size == 0;
+}
+
 macro_rules! define_inner_struct {
     ($($global:ty)?) => {
         #[derive(Debug)]
@@ -60,24 +68,79 @@ where
         return &inner.alloc;
     }
 
-    pub(crate) fn expand_capacity_at_least(&mut self, capacity: L, layout: Layout) -> FlexArrResult<()> {
-        // Use the capacity function so this returns the MAX value for ZST.
-        let old_cap = self.capacity(layout.size());
+    /// Creates a new empty `Inner` with room for `capacity` elements of `layout`, using a
+    /// clone of this allocator. No elements are copied, that is up to the caller.
+    pub(crate) fn clone_buffer(&self, capacity: L, layout: Layout) -> FlexArrResult<Self>
+    where
+        A: Clone,
+    {
+        let mut inner = Self {
+            ptr:      Self::dangling(layout),
+            length:   L::ZERO_VALUE,
+            capacity: L::ZERO_VALUE,
+            alloc:    self.alloc.clone(),
+        };
+        inner.expand_capacity_to(capacity, layout)?;
+        return Ok(inner);
+    }
+
+    /// A dangling pointer that is aligned correctly for the type.
+    #[inline]
+    const fn dangling(layout: Layout) -> NonNull<u8> {
+        return unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) };
+    }
+
+    /// Grows the capacity to at least `capacity` using the amortized growth strategy.
+    /// The `old_cap` is the current capacity as returned by `capacity()`, it's passed
+    /// in since the callers already had to look it up.
+    #[cold]
+    pub(crate) fn expand_capacity_at_least(&mut self, old_cap: L, capacity: L, layout: Layout) -> FlexArrResult<()> {
+        let new_cap = Self::amortized_capacity(old_cap, capacity);
+        return self.expand_capacity_to(new_cap, layout);
+    }
+
+    /// Returns the capacity the amortized growth strategy picks when at least
+    /// `capacity` elements are needed and the current capacity is `old_cap`.
+    #[inline]
+    pub(crate) fn amortized_capacity(old_cap: L, capacity: L) -> L {
         // Increase the capacity by 50%
         // Also don't care if this overflows. Max ensures that will
         // at least get the capacity needed.
         let new_cap = old_cap.wrapping_add(old_cap >> L::ONE_VALUE);
         let new_cap = new_cap.max(capacity);
-        let new_cap = new_cap.max(L::from(8u8));
-
-        return self.expand_capacity_to(new_cap, layout);
+        return new_cap.max(L::from(8u8));
     }
 
+    #[inline]
     pub(crate) fn expand_capacity_to(&mut self, capacity: L, layout: Layout) -> FlexArrResult<()> {
-        if layout.size() == 0 {
+        return self.expand_capacity(capacity, layout, false);
+    }
+
+    /// The same as `expand_capacity_to()`, but any newly allocated memory is zeroed.
+    #[inline]
+    pub(crate) fn expand_capacity_zeroed_to(&mut self, capacity: L, layout: Layout) -> FlexArrResult<()> {
+        return self.expand_capacity(capacity, layout, true);
+    }
+
+    fn expand_capacity(&mut self, capacity: L, layout: Layout, zeroed: bool) -> FlexArrResult<()> {
+        if size_is_zst(layout.size()) {
             // Nothing needs allocated for a ZST.
             return Ok(());
         }
+        if capacity == L::ZERO_VALUE {
+            // Just like `Vec` asking for no room does not allocate anything.
+            return Ok(());
+        }
+
+        // When a usize fits into the length type, a byte count too large for the length type
+        // is caught here as a capacity overflow. Narrower length types can have byte counts
+        // that only fit into a usize, so those are left to `layout_array()`.
+        if L::try_from(usize::MAX).is_ok() {
+            let stride = L::usize_as_self(layout.pad_to_align().size());
+            if capacity.checked_mul(stride).is_none() {
+                return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+            }
+        }
 
         let Ok(usz_cap) = usize::try_from(capacity) else {
             return Err(FlexArrErr::new(ErrorReason::UsizeOverflow));
@@ -92,24 +155,102 @@ where
 
         // Grow or do a normal allocation.
         let ptr = if let Some(old_layout) = self.current_layout(layout) {
-            let Ok(ptr) = (unsafe { self.alloc.grow(self.ptr, old_layout, new_layout) }) else {
+            let ret = if zeroed {
+                unsafe { self.alloc.grow_zeroed(self.ptr, old_layout, new_layout) }
+            } else {
+                unsafe { self.alloc.grow(self.ptr, old_layout, new_layout) }
+            };
+            let Ok(ptr) = ret else {
                 return Err(FlexArrErr::new(ErrorReason::AllocFailure));
             };
             ptr
         } else {
             // There is no old layout so just allocate the new memory.
-            let Ok(ptr) = self.alloc.allocate(new_layout) else {
+            let ret = if zeroed {
+                self.alloc.allocate_zeroed(new_layout)
+            } else {
+                self.alloc.allocate(new_layout)
+            };
+            let Ok(ptr) = ret else {
                 return Err(FlexArrErr::new(ErrorReason::AllocFailure));
             };
             ptr
         };
 
+        self.ptr = ptr.cast();
+        self.capacity = Self::adopted_capacity(capacity, ptr.len(), layout);
+        return Ok(());
+    }
+
+    /// The allocator is allowed to return more memory than requested, like when it rounds up
+    /// to a size class. Any whole elements that fit in the returned `block_len` bytes are kept
+    /// as capacity, clamped to what `L` can hold. It never returns less than `capacity`.
+    #[inline]
+    fn adopted_capacity(capacity: L, block_len: usize, layout: Layout) -> L {
+        let elements = block_len / layout.pad_to_align().size();
+        let adopted = match L::try_from(elements) {
+            Ok(val) => val,
+            Err(_) => L::MAX_VALUE,
+        };
+        return adopted.max(capacity);
+    }
+
+    #[inline]
+    pub(crate) fn shrink_capacity_to(&mut self, capacity: L, layout: Layout) -> FlexArrResult<()> {
+        return self.shrink_capacity(capacity, layout, false);
+    }
+
+    /// The same as `shrink_capacity_to()`, but if the allocator fails to shrink the memory,
+    /// a new smaller block is allocated and the elements are copied over instead.
+    #[inline]
+    pub(crate) fn shrink_capacity_copy_to(&mut self, capacity: L, layout: Layout) -> FlexArrResult<()> {
+        return self.shrink_capacity(capacity, layout, true);
+    }
+
+    fn shrink_capacity(&mut self, capacity: L, layout: Layout, copy_fallback: bool) -> FlexArrResult<()> {
+        if size_is_zst(layout.size()) {
+            // Nothing was ever allocated for a ZST.
+            return Ok(());
+        }
+        if capacity >= self.capacity {
+            return Ok(());
+        }
+        let Some(old_layout) = self.current_layout(layout) else {
+            return Ok(());
+        };
+
+        if capacity == L::ZERO_VALUE {
+            // Just give all the memory back and go back to a dangling pointer
+            // that is aligned correctly for the type.
+            unsafe { self.alloc.deallocate(self.ptr, old_layout) };
+            self.ptr = Self::dangling(layout);
+            self.capacity = L::ZERO_VALUE;
+            return Ok(());
+        }
+
+        // This is smaller than the current layout so it's always valid.
+        let new_layout = layout_array(layout, capacity.as_usize())?;
+        let ptr = match unsafe { self.alloc.shrink(self.ptr, old_layout, new_layout) } {
+            Ok(ptr) => ptr,
+            Err(_) if copy_fallback => {
+                let Ok(ptr) = self.alloc.allocate(new_layout) else {
+                    return Err(FlexArrErr::new(ErrorReason::AllocFailure));
+                };
+                // Only the bytes that fit in the new block are needed, and copying bytes
+                // can not panic, so the old block is always valid until it is given back.
+                unsafe { ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.cast().as_ptr(), new_layout.size()) };
+                unsafe { self.alloc.deallocate(self.ptr, old_layout) };
+                ptr
+            },
+            Err(_) => return Err(FlexArrErr::new(ErrorReason::AllocFailure)),
+        };
+
         self.ptr = ptr.cast();
         self.capacity = capacity;
         return Ok(());
     }
 
-    fn current_layout(&self, layout: Layout) -> Option<Layout> {
+    pub(crate) fn current_layout(&self, layout: Layout) -> Option<Layout> {
         // Nothing has ever been allocated so there is no current layout.
         if self.capacity == L::ZERO_VALUE {
             return None;
@@ -135,7 +276,7 @@ where
 
     #[inline]
     pub(crate) const fn capacity(&self, item_sz: usize) -> L {
-        if item_sz == 0 {
+        if size_is_zst(item_sz) {
             return L::MAX_VALUE;
         }
         return self.capacity;
@@ -0,0 +1,465 @@
+//! Iterator types that are returned by various `FlexArr` methods.
+
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr;
+use core::ptr::NonNull;
+use core::slice;
+
+use super::FlexArr;
+use super::FlexSlice;
+use crate::alloc::AltAllocator;
+use crate::types::LengthType;
+
+/// An iterator over overlapping windows of a `FlexArr` yielding `&FlexSlice` views.
+///
+/// This is created by `FlexArr::windows()`.
+#[derive(Debug, Clone)]
+pub struct Windows<'a, T, L: LengthType = u32>
+where
+    usize: TryFrom<L>,
+{
+    iter: slice::Windows<'a, T>,
+    _ph:  PhantomData<L>,
+}
+
+impl<'a, T, L: LengthType> Windows<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    pub(crate) fn new(iter: slice::Windows<'a, T>) -> Self {
+        return Self {
+            iter: iter,
+            _ph:  PhantomData,
+        };
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> Iterator for Windows<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a FlexSlice<T, L>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.iter.next().map(FlexSlice::from_slice);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> DoubleEndedIterator for Windows<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.iter.next_back().map(FlexSlice::from_slice);
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> ExactSizeIterator for Windows<'a, T, L> where usize: TryFrom<L> {}
+impl<'a, T, L: LengthType + 'a> FusedIterator for Windows<'a, T, L> where usize: TryFrom<L> {}
+
+/// An iterator over non-overlapping chunks of a `FlexArr` yielding `&FlexSlice` views.
+/// The last chunk may be shorter than the requested size.
+///
+/// This is created by `FlexArr::chunks()`.
+#[derive(Debug, Clone)]
+pub struct Chunks<'a, T, L: LengthType = u32>
+where
+    usize: TryFrom<L>,
+{
+    iter: slice::Chunks<'a, T>,
+    _ph:  PhantomData<L>,
+}
+
+impl<'a, T, L: LengthType> Chunks<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    pub(crate) fn new(iter: slice::Chunks<'a, T>) -> Self {
+        return Self {
+            iter: iter,
+            _ph:  PhantomData,
+        };
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> Iterator for Chunks<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a FlexSlice<T, L>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.iter.next().map(FlexSlice::from_slice);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> DoubleEndedIterator for Chunks<'a, T, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        return self.iter.next_back().map(FlexSlice::from_slice);
+    }
+}
+
+impl<'a, T, L: LengthType + 'a> ExactSizeIterator for Chunks<'a, T, L> where usize: TryFrom<L> {}
+impl<'a, T, L: LengthType + 'a> FusedIterator for Chunks<'a, T, L> where usize: TryFrom<L> {}
+
+/// A consuming iterator that moves the elements out of a `FlexArr` in arrays of `N`
+/// elements, starting from the back. When fewer than `N` elements are left at the
+/// front the iterator ends, and those are available from `remainder()`.
+///
+/// Any elements that were not yielded are dropped along with the iterator.
+///
+/// This is created by `FlexArr::into_rchunks()`.
+#[derive(Debug)]
+pub struct IntoRChunks<T, A: AltAllocator, L: LengthType, const N: usize>
+where
+    usize: TryFrom<L>,
+{
+    arr: FlexArr<T, A, L>,
+}
+
+impl<T, A: AltAllocator, L: LengthType, const N: usize> IntoRChunks<T, A, L, N>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    pub(crate) fn new(arr: FlexArr<T, A, L>) -> Self {
+        assert!(N != 0, "chunk size must be non-zero");
+        return Self {
+            arr: arr
+        };
+    }
+
+    /// Returns the elements at the front that do not fill a whole chunk.
+    #[inline]
+    pub fn remainder(&self) -> &[T] {
+        let slc = self.arr.as_slice();
+        return &slc[..slc.len() % N];
+    }
+
+    /// Stops iterating and gives back a `FlexArr` holding every element that
+    /// has not been yielded yet, which keeps the original memory.
+    #[inline]
+    pub fn into_remainder(self) -> FlexArr<T, A, L> {
+        return self.arr;
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType, const N: usize> Iterator for IntoRChunks<T, A, L, N>
+where
+    usize: TryFrom<L>,
+{
+    type Item = [T; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.arr.len_usize();
+        if len < N {
+            return None;
+        }
+        let new_len = len - N;
+        // Safety: The chunk is in bounds, and the length is lowered
+        // so the elements read out are no longer owned by the array.
+        unsafe {
+            self.arr.set_len(L::usize_as_self(new_len));
+            let chunk = self.arr.as_ptr().add(new_len).cast::<[T; N]>();
+            return Some(chunk.read());
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.arr.len_usize() / N;
+        return (left, Some(left));
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType, const N: usize> ExactSizeIterator for IntoRChunks<T, A, L, N> where
+    usize: TryFrom<L>
+{
+}
+impl<T, A: AltAllocator, L: LengthType, const N: usize> FusedIterator for IntoRChunks<T, A, L, N> where usize: TryFrom<L>
+{}
+
+/// A draining iterator that moves a range of elements out of a `FlexArr`. The elements
+/// can be taken from either end.
+///
+/// When the `Drain` is dropped any elements in the range that were not yielded are dropped,
+/// and the elements after the range are moved down to close the gap. Use `keep_rest()` to
+/// keep the elements that were not yielded instead.
+///
+/// This is created by `FlexArr::drain()`.
+#[derive(Debug)]
+pub struct Drain<'a, T, A: AltAllocator, L: LengthType>
+where
+    usize: TryFrom<L>,
+{
+    // Where the elements after the drained range start, and how many there are.
+    tail_start: usize,
+    tail_len:   usize,
+    // Where the first element that was not yielded yet is.
+    front:      usize,
+    iter:       slice::Iter<'a, T>,
+    arr:        NonNull<FlexArr<T, A, L>>,
+    _ph:        PhantomData<&'a mut FlexArr<T, A, L>>,
+}
+
+impl<'a, T, A: AltAllocator, L: LengthType> Drain<'a, T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    /// The caller must have already checked the range is in bounds.
+    #[inline]
+    pub(crate) fn new(arr: &'a mut FlexArr<T, A, L>, start: usize, end: usize) -> Self {
+        let len = arr.len_usize();
+        // Until the `Drain` is dropped the array only owns the elements before the range.
+        // That way if the `Drain` is leaked, the rest are leaked too instead of being
+        // dropped twice.
+        unsafe { arr.set_len(L::usize_as_self(start)) };
+        let iter = unsafe { slice::from_raw_parts(arr.as_ptr().add(start), end - start) }.iter();
+        return Self {
+            tail_start: end,
+            tail_len:   len - end,
+            front:      start,
+            iter:       iter,
+            arr:        NonNull::from(arr),
+            _ph:        PhantomData,
+        };
+    }
+
+    /// Returns the elements that have not been yielded yet as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        return self.iter.as_slice();
+    }
+
+    /// Stops draining and keeps every element that has not been yielded yet in the
+    /// `FlexArr`, in their original order. The elements after the range are moved
+    /// down right after them.
+    pub fn keep_rest(self) {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        let unyielded = this.iter.as_slice();
+        let count = unyielded.len();
+        let src = unyielded.as_ptr();
+        let arr = unsafe { this.arr.as_mut() };
+        let start = arr.len_usize();
+        let base = arr.as_mut_ptr();
+        unsafe {
+            // Either copy may overlap with where it is moving to.
+            ptr::copy(src, base.add(start), count);
+            ptr::copy(base.add(this.tail_start), base.add(start + count), this.tail_len);
+            arr.set_len(L::usize_as_self(start + count + this.tail_len));
+        }
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> Iterator for Drain<'_, T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let item = self.iter.next()?;
+        self.front += 1;
+        // Safety: each element is only yielded once, and the array no longer owns it.
+        return Some(unsafe { ptr::read(item) });
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.iter.size_hint();
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> DoubleEndedIterator for Drain<'_, T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        return self.iter.next_back().map(|item| unsafe { ptr::read(item) });
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> ExactSizeIterator for Drain<'_, T, A, L> where usize: TryFrom<L> {}
+impl<T, A: AltAllocator, L: LengthType> FusedIterator for Drain<'_, T, A, L> where usize: TryFrom<L> {}
+
+// Just like `FlexArr` itself, the pointer would otherwise stop a `Drain` from being
+// sent or shared even though it is only a unique borrow of the array.
+unsafe impl<T: Send, A: AltAllocator + Send, L: LengthType + Send> Send for Drain<'_, T, A, L> where usize: TryFrom<L> {}
+unsafe impl<T: Sync, A: AltAllocator + Sync, L: LengthType + Sync> Sync for Drain<'_, T, A, L> where usize: TryFrom<L> {}
+
+impl<T, A: AltAllocator, L: LengthType> Drop for Drain<'_, T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn drop(&mut self) {
+        // Moves the tail down even if dropping one of the remaining elements panics.
+        struct MoveTailOnDrop<'r, 'a, T, A: AltAllocator, L: LengthType>(&'r mut Drain<'a, T, A, L>)
+        where
+            usize: TryFrom<L>;
+
+        impl<T, A: AltAllocator, L: LengthType> Drop for MoveTailOnDrop<'_, '_, T, A, L>
+        where
+            usize: TryFrom<L>,
+        {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let arr = unsafe { drain.arr.as_mut() };
+                let start = arr.len_usize();
+                let base = arr.as_mut_ptr();
+                unsafe {
+                    ptr::copy(base.add(drain.tail_start), base.add(start), drain.tail_len);
+                    arr.set_len(L::usize_as_self(start + drain.tail_len));
+                }
+            }
+        }
+
+        let iter = core::mem::take(&mut self.iter);
+        let remaining = iter.len();
+        let arr = unsafe { self.arr.as_mut() };
+        let start = arr.len_usize();
+
+        if FlexArr::<T, A, L>::is_zst() {
+            // Zero sized values are all the same, so nothing needs to move. Just count the
+            // remaining ones as part of the array again and truncate them away.
+            unsafe { arr.set_len(L::usize_as_self(start + remaining + self.tail_len)) };
+            arr.truncate(L::usize_as_self(start + self.tail_len));
+            return;
+        }
+
+        // The remaining elements are found through the array's own pointer, since the slice
+        // from the iterator can only be used to read.
+        let to_drop = ptr::slice_from_raw_parts_mut(unsafe { arr.as_mut_ptr().add(self.front) }, remaining);
+        let _guard = MoveTailOnDrop(self);
+        unsafe { ptr::drop_in_place(to_drop) };
+    }
+}
+
+/// A consuming iterator that moves the elements out of a `FlexArr`, from either end.
+///
+/// Any elements that were not yielded are dropped along with the iterator, and the
+/// memory is given back to the allocator.
+///
+/// This is created by calling `into_iter()` on a `FlexArr`.
+pub struct IntoIter<T, A: AltAllocator, L: LengthType>
+where
+    usize: TryFrom<L>,
+{
+    // The elements in `start..end` have not been yielded yet.
+    start: usize,
+    end:   usize,
+    // The length is kept at zero so the array only owns the memory.
+    arr:   FlexArr<T, A, L>,
+}
+
+impl<T, A: AltAllocator, L: LengthType> IntoIter<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    pub(crate) fn new(mut arr: FlexArr<T, A, L>) -> Self {
+        let end = arr.len_usize();
+        // If the `IntoIter` is leaked, the elements are leaked too instead of being
+        // dropped twice.
+        unsafe { arr.set_len(L::ZERO_VALUE) };
+        return Self {
+            start: 0,
+            end:   end,
+            arr:   arr,
+        };
+    }
+
+    /// Returns the elements that have not been yielded yet as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        return unsafe { slice::from_raw_parts(self.arr.as_ptr().add(self.start), self.end - self.start) };
+    }
+
+    /// Returns the elements that have not been yielded yet as a mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        return unsafe { slice::from_raw_parts_mut(self.arr.as_mut_ptr().add(self.start), self.end - self.start) };
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> Iterator for IntoIter<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        // Safety: each element is only read out once, since the start is moved past it.
+        let item = unsafe { self.arr.as_ptr().add(self.start).read() };
+        self.start += 1;
+        return Some(item);
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.end - self.start;
+        return (left, Some(left));
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> DoubleEndedIterator for IntoIter<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        return Some(unsafe { self.arr.as_ptr().add(self.end).read() });
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> ExactSizeIterator for IntoIter<T, A, L> where usize: TryFrom<L> {}
+impl<T, A: AltAllocator, L: LengthType> FusedIterator for IntoIter<T, A, L> where usize: TryFrom<L> {}
+
+impl<T: core::fmt::Debug, A: AltAllocator, L: LengthType> core::fmt::Debug for IntoIter<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return f.debug_tuple("IntoIter").field(&self.as_slice()).finish();
+    }
+}
+
+impl<T, A: AltAllocator, L: LengthType> Drop for IntoIter<T, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn drop(&mut self) {
+        // The array is dropped after this, which frees the memory even if
+        // dropping one of the remaining elements panics.
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+    }
+}
@@ -0,0 +1,191 @@
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ops::DerefMut;
+use core::ops::Index;
+use core::ops::IndexMut;
+
+use crate::types::LengthType;
+
+/// `FlexSlice` is a borrowed view into a contiguous run of elements, much like `[T]`, but
+/// its length and indexing operations use the `LengthType` `L` instead of `usize`.
+///
+/// It is what `FlexArr` hands out when a sub-view is requested. That way one can stay
+/// in the same index space as the `FlexArr` it came from. It also derefs to `[T]` so
+/// all the usual slice methods are still available.
+#[repr(transparent)]
+pub struct FlexSlice<T, L: LengthType = u32>
+where
+    usize: TryFrom<L>,
+{
+    _ph:  PhantomData<L>,
+    data: [T],
+}
+
+impl<T, L: LengthType> FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Creates a `FlexSlice` view from a slice.
+    ///
+    /// The caller must make sure the length of the slice fits into `L`.
+    #[inline]
+    pub(crate) const fn from_slice(slice: &[T]) -> &Self {
+        // Safety: `FlexSlice` is `repr(transparent)` over `[T]`.
+        return unsafe { &*(slice as *const [T] as *const Self) };
+    }
+
+    /// Creates a mutable `FlexSlice` view from a mutable slice.
+    ///
+    /// The caller must make sure the length of the slice fits into `L`.
+    #[inline]
+    pub(crate) const fn from_mut_slice(slice: &mut [T]) -> &mut Self {
+        // Safety: `FlexSlice` is `repr(transparent)` over `[T]`.
+        return unsafe { &mut *(slice as *mut [T] as *mut Self) };
+    }
+
+    /// Returns the number of elements in the `FlexSlice`.
+    #[inline]
+    pub fn len(&self) -> L {
+        return L::usize_as_self(self.data.len());
+    }
+
+    /// Determines if the `FlexSlice` is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        return self.data.is_empty();
+    }
+
+    /// Returns a reference to the element at the specified `index`,
+    /// or `None` if the index is out of bounds.
+    pub fn get(&self, index: L) -> Option<&T> {
+        let Ok(i) = usize::try_from(index) else {
+            return None;
+        };
+        return self.data.get(i);
+    }
+
+    /// Returns a mutable reference to the element at the specified `index`,
+    /// or `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: L) -> Option<&mut T> {
+        let Ok(i) = usize::try_from(index) else {
+            return None;
+        };
+        return self.data.get_mut(i);
+    }
+
+    /// Returns the view as a plain slice.
+    #[inline]
+    pub const fn as_slice(&self) -> &[T] {
+        return &self.data;
+    }
+
+    /// Returns the view as a plain mutable slice.
+    #[inline]
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        return &mut self.data;
+    }
+}
+
+/// # Note on Indexing
+/// Just like `[]` on a `FlexArr`, an `index >= length` will panic. This can
+/// also panic if the index value is too large to fit into a `usize`.
+impl<T, L: LengthType> Index<L> for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Output = T;
+    fn index(&self, index: L) -> &Self::Output {
+        let Ok(i) = usize::try_from(index) else {
+            panic!("Index cannot be converted to usize");
+        };
+        return &self.data[i];
+    }
+}
+
+/// # Note on Indexing
+/// Just like `[]` on a `FlexArr`, an `index >= length` will panic. This can
+/// also panic if the index value is too large to fit into a `usize`.
+impl<T, L: LengthType> IndexMut<L> for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    fn index_mut(&mut self, index: L) -> &mut Self::Output {
+        let Ok(i) = usize::try_from(index) else {
+            panic!("Index cannot be converted to usize");
+        };
+        return &mut self.data[i];
+    }
+}
+
+impl<T, L: LengthType> Deref for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        return &self.data;
+    }
+}
+
+impl<T, L: LengthType> DerefMut for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        return &mut self.data;
+    }
+}
+
+impl<T: core::fmt::Debug, L: LengthType> core::fmt::Debug for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return self.data.fmt(f);
+    }
+}
+
+impl<T: PartialEq, L: LengthType> PartialEq<[T]> for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    fn eq(&self, other: &[T]) -> bool {
+        return self.data == *other;
+    }
+}
+
+impl<T: PartialEq, L: LengthType, const N: usize> PartialEq<[T; N]> for FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    fn eq(&self, other: &[T; N]) -> bool {
+        return self.data == *other;
+    }
+}
+
+impl<'a, T, L: LengthType> IntoIterator for &'a FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.data.iter();
+    }
+}
+
+impl<'a, T, L: LengthType> IntoIterator for &'a mut FlexSlice<T, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.data.iter_mut();
+    }
+}
@@ -0,0 +1,540 @@
+use core::mem::ManuallyDrop;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::ops::DerefMut;
+use core::ptr;
+use core::slice;
+
+use super::FlexArr;
+use crate::alloc::AltAllocator;
+#[cfg(feature = "std_alloc")]
+use crate::alloc::Global;
+use crate::types::ErrorReason;
+use crate::types::FlexArrErr;
+use crate::types::FlexArrResult;
+use crate::types::LengthType;
+
+/// Where the elements of a `SmallFlexArr` are stored. Only one of the two is ever kept,
+/// so inline elements do not also pay for an unused `FlexArr`, and the other way around.
+enum Storage<T, const N: usize, A: AltAllocator, L: LengthType>
+where
+    usize: TryFrom<L>,
+{
+    Inline {
+        buf:   [MaybeUninit<T>; N],
+        len:   L,
+        alloc: A,
+    },
+    Heap(FlexArr<T, A, L>),
+}
+
+macro_rules! define_small_array_struct {
+    ($($global:ty)?) => {
+        /// `SmallFlexArr` is a `FlexArr` that keeps up to `N` elements inline, and only
+        /// moves them to memory from the allocator once it grows past `N`.
+        ///
+        /// For arrays that are usually tiny this avoids allocating at all. While the elements
+        /// are inline `capacity()` reports `N`, or the largest value the `LengthType` can hold
+        /// if `N` is larger than that. Once the elements spill over to the allocator they stay
+        /// there. Zero sized types never need memory, so they always act as if they spilled.
+        ///
+        /// The inline buffer and the allocated `FlexArr` are never stored at the same time, so
+        /// a `SmallFlexArr` is only about as large as the bigger of the two.
+        pub struct SmallFlexArr<T, const N: usize, A: AltAllocator $(= $global)?, L: LengthType = u32>
+        where
+            usize: TryFrom<L>,
+        {
+            data: Storage<T, N, A, L>,
+        }
+    };
+}
+
+#[cfg(feature = "std_alloc")]
+define_small_array_struct!(Global);
+
+#[cfg(not(feature = "std_alloc"))]
+define_small_array_struct!();
+
+impl<T, const N: usize, A: AltAllocator, L: LengthType> SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    const IS_ZST: bool = FlexArr::<T, A, L>::is_zst();
+
+    /// Constructs a new, empty `SmallFlexArr` using the given allocator.
+    ///
+    /// Nothing is allocated until more than `N` elements are added.
+    pub const fn new_in(alloc: A) -> Self {
+        if Self::IS_ZST {
+            return Self {
+                data: Storage::Heap(FlexArr::new_in(alloc)),
+            };
+        }
+        return Self {
+            data: Storage::Inline {
+                buf:   [const { MaybeUninit::uninit() }; N],
+                len:   L::ZERO_VALUE,
+                alloc: alloc,
+            },
+        };
+    }
+
+    /// Determines if the elements are still stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        return matches!(self.data, Storage::Inline { .. });
+    }
+
+    /// Returns the number of elements in the `SmallFlexArr`.
+    #[inline]
+    pub fn len(&self) -> L {
+        return match &self.data {
+            Storage::Inline {
+                len, ..
+            } => *len,
+            Storage::Heap(heap) => heap.len(),
+        };
+    }
+
+    /// Determines if the `SmallFlexArr` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        return self.len() == L::ZERO_VALUE;
+    }
+
+    /// Returns the number of elements `SmallFlexArr` can store without needing to allocate.
+    ///
+    /// This is `N` while the elements are inline, clamped to the maximum value of the
+    /// `LengthType`, and the capacity of the allocated memory after they spilled.
+    #[inline]
+    pub fn capacity(&self) -> L {
+        return match &self.data {
+            Storage::Inline {
+                ..
+            } => Self::inline_capacity(),
+            Storage::Heap(heap) => heap.capacity(),
+        };
+    }
+
+    /// Appends an element to the end of the `SmallFlexArr`.
+    ///
+    /// If the inline storage is full, all the elements are moved to memory from the
+    /// allocator first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory allocation fails or the length would overflow.
+    pub fn push(&mut self, item: T) -> FlexArrResult<()> {
+        let (buf, len) = match &mut self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } if *len < Self::inline_capacity() => (buf, len),
+            _ => return self.spill(L::ONE_VALUE)?.push(item),
+        };
+        buf[len.as_usize()].write(item);
+        *len = *len + L::ONE_VALUE;
+        return Ok(());
+    }
+
+    /// Removes and returns the last element from the `SmallFlexArr`.
+    ///
+    /// If the array is empty, this method returns `None`.
+    pub fn pop(&mut self) -> Option<T> {
+        let (buf, len) = match &mut self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } => (buf, len),
+            Storage::Heap(heap) => return heap.pop(),
+        };
+        if *len == L::ZERO_VALUE {
+            return None;
+        }
+        *len = *len - L::ONE_VALUE;
+        // The length was just reduced, so this element is no longer tracked.
+        let item = unsafe { buf[len.as_usize()].assume_init_read() };
+        return Some(item);
+    }
+
+    /// Inserts an element at the specified `index`, shifting all elements after it to the right.
+    ///
+    /// If the inline storage is full, all the elements are moved to memory from the
+    /// allocator first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory allocation fails or the length would overflow.
+    ///
+    /// Additionally, can return `FlexArrErr` with a reason of `IndexOutOfBounds` if the index is out of bounds.
+    pub fn insert(&mut self, index: L, item: T) -> FlexArrResult<()> {
+        if let Storage::Inline {
+            buf,
+            len,
+            ..
+        } = &mut self.data
+        {
+            if index > *len {
+                return Err(FlexArrErr::new(ErrorReason::IndexOutOfBounds));
+            }
+            if *len < Self::inline_capacity() {
+                let pos = unsafe { buf.as_mut_ptr().cast::<T>().add(index.as_usize()) };
+                unsafe { ptr::copy(pos, pos.add(1), (*len - index).as_usize()) };
+                unsafe { ptr::write(pos, item) };
+                *len = *len + L::ONE_VALUE;
+                return Ok(());
+            }
+        }
+        return self.spill(L::ONE_VALUE)?.insert(index, item);
+    }
+
+    /// Removes and returns the element at the specified `index`, shifting all elements
+    /// after it to the left.
+    ///
+    /// If the `index` is out of bounds, this method returns `None`.
+    pub fn remove(&mut self, index: L) -> Option<T> {
+        let (buf, len) = match &mut self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } => (buf, len),
+            Storage::Heap(heap) => return heap.remove(index),
+        };
+        if index >= *len {
+            return None;
+        }
+        *len = *len - L::ONE_VALUE;
+        let pos = unsafe { buf.as_mut_ptr().cast::<T>().add(index.as_usize()) };
+        let item = unsafe { ptr::read(pos) };
+        unsafe { ptr::copy(pos.add(1), pos, (*len - index).as_usize()) };
+        return Some(item);
+    }
+
+    /// Reduces the length of the `SmallFlexArr` to the specified value, dropping all elements
+    /// beyond that point.
+    ///
+    /// If the provided `length` is greater than or equal to the current length, the method does nothing.
+    /// Elements that already spilled to the allocator stay there.
+    pub fn truncate(&mut self, length: L) {
+        let (buf, len) = match &mut self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } => (buf, len),
+            Storage::Heap(heap) => return heap.truncate(length),
+        };
+        if length >= *len {
+            return;
+        }
+        let removed = (*len - length).as_usize();
+        // The length is reduced first, so a panicking drop can not lead to a double drop.
+        *len = length;
+        let loc = unsafe { buf.as_mut_ptr().cast::<T>().add(length.as_usize()) };
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(loc, removed)) };
+    }
+
+    /// Clears all elements from the `SmallFlexArr`, dropping each of them.
+    ///
+    /// Elements that already spilled to the allocator keep their memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(L::ZERO_VALUE);
+    }
+
+    /// Appends a slice of `T` elements to the end of the `SmallFlexArr`.
+    ///
+    /// The elements are copied inline if they all fit, otherwise everything is moved to memory
+    /// from the allocator with room for the whole slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FlexArrErr` if memory allocation fails or the length would overflow.
+    pub fn extend_from_slice(&mut self, slice: &[T]) -> FlexArrResult<()>
+    where
+        T: Copy,
+    {
+        let Ok(additional) = L::try_from(slice.len()) else {
+            return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+        };
+        if let Storage::Inline {
+            buf,
+            len,
+            ..
+        } = &mut self.data
+        {
+            let fits = len
+                .checked_add(additional)
+                .is_some_and(|new_len| new_len <= Self::inline_capacity());
+            if fits {
+                let dst = unsafe { buf.as_mut_ptr().cast::<T>().add(len.as_usize()) };
+                unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), dst, slice.len()) };
+                *len = *len + additional;
+                return Ok(());
+            }
+        }
+        return self.spill(additional)?.extend_from_slice(slice);
+    }
+
+    /// Returns a reference to the element at the specified `index`,
+    /// or `None` if the index is out of bounds.
+    pub fn get(&self, index: L) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        return self.as_slice().get(index.as_usize());
+    }
+
+    /// Returns a mutable reference to the element at the specified `index`,
+    /// or `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: L) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+        return self.as_mut_slice().get_mut(index.as_usize());
+    }
+
+    /// Returns a reference to the elements as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        return match &self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } => unsafe { slice::from_raw_parts(buf.as_ptr().cast(), len.as_usize()) },
+            Storage::Heap(heap) => heap.as_slice(),
+        };
+    }
+
+    /// Returns a mutable reference to the elements as a slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        return match &mut self.data {
+            Storage::Inline {
+                buf,
+                len,
+                ..
+            } => unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), len.as_usize()) },
+            Storage::Heap(heap) => heap.as_mut_slice(),
+        };
+    }
+
+    /// Returns a reference to the current allocator.
+    #[inline]
+    pub const fn allocator(array: &Self) -> &A {
+        return match &array.data {
+            Storage::Inline {
+                alloc, ..
+            } => alloc,
+            Storage::Heap(heap) => FlexArr::allocator(heap),
+        };
+    }
+
+    fn inline_capacity() -> L {
+        let Ok(cap) = L::try_from(N) else {
+            return L::MAX_VALUE;
+        };
+        return cap;
+    }
+
+    /// Moves the inline elements to memory from the allocator with room for `additional` more,
+    /// and returns the `FlexArr` now holding them. If they already spilled it is just returned.
+    #[cold]
+    fn spill(&mut self, additional: L) -> FlexArrResult<&mut FlexArr<T, A, L>> {
+        if let Storage::Inline {
+            buf,
+            len,
+            alloc,
+        } = &mut self.data
+        {
+            let Some(needed) = len.checked_add(additional) else {
+                return Err(FlexArrErr::new(ErrorReason::CapacityOverflow));
+            };
+            // The allocator is only moved out once everything succeeded. Until then `self`
+            // still owns it, so the copy must not be dropped if reserving fails or panics.
+            let mut heap = ManuallyDrop::new(FlexArr::new_in(unsafe { ptr::read(alloc) }));
+            heap.reserve(needed)?;
+            // The heap is empty and has room for all of the inline elements.
+            let src = buf.as_ptr().cast::<T>();
+            unsafe { ptr::copy_nonoverlapping(src, heap.as_mut_ptr(), len.as_usize()) };
+            unsafe { heap.set_len(*len) };
+            // The elements and the allocator now belong to the heap, so the inline
+            // storage is overwritten without dropping it.
+            unsafe { ptr::write(&mut self.data, Storage::Heap(ManuallyDrop::into_inner(heap))) };
+        }
+        let Storage::Heap(heap) = &mut self.data else {
+            unreachable!("the inline elements were just moved to the allocator");
+        };
+        return Ok(heap);
+    }
+}
+
+#[cfg(feature = "std_alloc")]
+impl<T, const N: usize, L: LengthType> SmallFlexArr<T, N, Global, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Constructs a new, empty `SmallFlexArr` using the standard allocator.
+    ///
+    /// Nothing is allocated until more than `N` elements are added.
+    #[inline]
+    pub const fn new() -> Self {
+        return Self::new_in(Global);
+    }
+}
+
+#[cfg(feature = "std_alloc")]
+impl<T, const N: usize, L: LengthType> Default for SmallFlexArr<T, N, Global, L>
+where
+    usize: TryFrom<L>,
+{
+    /// Creates an empty `SmallFlexArr` using the standard allocator. The same as `SmallFlexArr::new()`.
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// # Panics
+///
+/// Cloning panics if the elements spilled and the memory for the copy can not be allocated.
+impl<T: Clone, const N: usize, A: AltAllocator + Clone, L: LengthType> Clone for SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn clone(&self) -> Self {
+        let alloc = match &self.data {
+            Storage::Inline {
+                alloc, ..
+            } => alloc.clone(),
+            Storage::Heap(heap) => {
+                return Self {
+                    data: Storage::Heap(heap.clone()),
+                };
+            },
+        };
+        // The elements are pushed one at a time, so if a clone panics the ones
+        // already cloned are dropped along with `arr`.
+        let mut arr = Self::new_in(alloc);
+        for item in self.as_slice() {
+            // `self` is inline, so there is always room inline and nothing is allocated.
+            let pushed = arr.push(item.clone());
+            debug_assert!(pushed.is_ok());
+        }
+        return arr;
+    }
+}
+
+impl<T, const N: usize, A: AltAllocator, L: LengthType> Drop for SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn drop(&mut self) {
+        // Once spilled the `FlexArr` drops the elements itself.
+        if self.is_inline() {
+            unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+        }
+    }
+}
+
+impl<T, const N: usize, A: AltAllocator, L: LengthType> Deref for SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        return self.as_slice();
+    }
+}
+
+impl<T, const N: usize, A: AltAllocator, L: LengthType> DerefMut for SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        return self.as_mut_slice();
+    }
+}
+
+impl<T: core::fmt::Debug, const N: usize, A: AltAllocator, L: LengthType> core::fmt::Debug for SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return self.as_slice().fmt(f);
+    }
+}
+
+impl<T, U, const N: usize, const M: usize, A1, A2, L1, L2> PartialEq<SmallFlexArr<U, M, A2, L2>>
+    for SmallFlexArr<T, N, A1, L1>
+where
+    T: PartialEq<U>,
+    A1: AltAllocator,
+    A2: AltAllocator,
+    L1: LengthType,
+    L2: LengthType,
+    usize: TryFrom<L1>,
+    usize: TryFrom<L2>,
+{
+    /// Compares the elements only, so arrays with a different inline size, allocator
+    /// or length type are equal when their contents are.
+    #[inline]
+    fn eq(&self, other: &SmallFlexArr<U, M, A2, L2>) -> bool {
+        return self.as_slice() == other.as_slice();
+    }
+}
+
+impl<T, U, const N: usize, A: AltAllocator, L: LengthType> PartialEq<[U]> for SmallFlexArr<T, N, A, L>
+where
+    T: PartialEq<U>,
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        return self.as_slice() == other;
+    }
+}
+
+impl<T, U, const N: usize, A: AltAllocator, L: LengthType, const M: usize> PartialEq<[U; M]>
+    for SmallFlexArr<T, N, A, L>
+where
+    T: PartialEq<U>,
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn eq(&self, other: &[U; M]) -> bool {
+        return self.as_slice() == other;
+    }
+}
+
+impl<T: Eq, const N: usize, A: AltAllocator, L: LengthType> Eq for SmallFlexArr<T, N, A, L> where usize: TryFrom<L> {}
+
+impl<'a, T, const N: usize, A: AltAllocator, L: LengthType> IntoIterator for &'a SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.as_slice().iter();
+    }
+}
+
+impl<'a, T, const N: usize, A: AltAllocator, L: LengthType> IntoIterator for &'a mut SmallFlexArr<T, N, A, L>
+where
+    usize: TryFrom<L>,
+{
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.as_mut_slice().iter_mut();
+    }
+}
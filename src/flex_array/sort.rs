@@ -0,0 +1,147 @@
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+// Runs at most this long are sorted with insertion sort, so short
+// arrays never need the scratch buffer.
+pub(crate) const INSERTION_LEN: usize = 20;
+
+/// Returns how many elements the scratch buffer passed to `merge_sort()`
+/// needs to hold for a slice of `len` elements.
+#[inline]
+pub(crate) const fn scratch_len(len: usize) -> usize {
+    if len <= INSERTION_LEN {
+        return 0;
+    }
+    return len / 2;
+}
+
+/// A stable merge sort. `buf` must point to space for at least
+/// `scratch_len(v.len())` elements that is not aliased by `v`.
+///
+/// If `is_less` panics every element is still in `v` exactly once,
+/// although the order is unspecified.
+pub(crate) unsafe fn merge_sort<T, F>(v: &mut [T], buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    if len <= INSERTION_LEN {
+        insertion_sort(v, is_less);
+        return;
+    }
+
+    let mid = len / 2;
+    unsafe { merge_sort(&mut v[..mid], buf, is_less) };
+    unsafe { merge_sort(&mut v[mid..], buf, is_less) };
+
+    // The two halves are already in order, so there is nothing to merge.
+    if !is_less(&v[mid], &v[mid - 1]) {
+        return;
+    }
+    unsafe { merge(v, mid, buf, is_less) };
+}
+
+fn insertion_sort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..v.len() {
+        unsafe { insert_tail(&mut v[..=i], is_less) };
+    }
+}
+
+// Writes the held element into `dest` when dropped, that way
+// the slice is whole again even if `is_less` panics.
+struct InsertionHole<T> {
+    src:  *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for InsertionHole<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dest, 1) };
+    }
+}
+
+/// Moves the last element of `v` back into place, given everything before it is sorted.
+unsafe fn insert_tail<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let arr = v.as_mut_ptr();
+    let i = v.len() - 1;
+    unsafe {
+        if !is_less(&*arr.add(i), &*arr.add(i - 1)) {
+            return;
+        }
+        let tmp = ManuallyDrop::new(ptr::read(arr.add(i)));
+        let mut hole = InsertionHole {
+            src:  &*tmp,
+            dest: arr.add(i - 1),
+        };
+        ptr::copy_nonoverlapping(arr.add(i - 1), arr.add(i), 1);
+
+        for j in (0..i - 1).rev() {
+            if !is_less(&*tmp, &*arr.add(j)) {
+                break;
+            }
+            ptr::copy_nonoverlapping(arr.add(j), arr.add(j + 1), 1);
+            hole.dest = arr.add(j);
+        }
+        // The hole being dropped writes `tmp` into its final spot.
+    }
+}
+
+// Holds the part of the left run that has not been merged yet. When dropped
+// it gets copied into the gap in the slice which is exactly the same size.
+struct MergeHole<T> {
+    start: *mut T,
+    end:   *mut T,
+    dest:  *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        let left = unsafe { self.end.offset_from(self.start) } as usize;
+        unsafe { ptr::copy_nonoverlapping(self.start, self.dest, left) };
+    }
+}
+
+/// Merges the sorted runs `v[..mid]` and `v[mid..]`. The left run is moved into `buf`
+/// and then merged back into `v` from the front. Taking from the left run on ties
+/// keeps the sort stable.
+unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    let arr = v.as_mut_ptr();
+    unsafe {
+        ptr::copy_nonoverlapping(arr, buf, mid);
+        let mut hole = MergeHole {
+            start: buf,
+            end:   buf.add(mid),
+            dest:  arr,
+        };
+
+        let mut right = arr.add(mid);
+        let right_end = arr.add(len);
+        while hole.start < hole.end && right < right_end {
+            let take_right = is_less(&*right, &*hole.start);
+            let src = if take_right {
+                right
+            } else {
+                hole.start
+            };
+            ptr::copy_nonoverlapping(src, hole.dest, 1);
+            if take_right {
+                right = right.add(1);
+            } else {
+                hole.start = hole.start.add(1);
+            }
+            hole.dest = hole.dest.add(1);
+        }
+        // Whatever remains of the right run is already in place, and dropping
+        // the hole moves what remains of the left run in front of it.
+    }
+}
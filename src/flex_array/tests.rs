@@ -3,13 +3,19 @@ use core::mem::size_of;
 use core::mem::size_of_val;
 use core::ptr::NonNull;
 use core::ptr::dangling_mut;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
 
 use super::FlexArr;
 use super::inner::Inner;
 use crate::alloc::AllocError;
 use crate::alloc::AltAllocator;
 use crate::types::ErrorReason;
+use crate::types::FlexArrErr;
+use crate::types::FlexArrResult;
+use crate::types::ReserveError;
 
+#[derive(Debug)]
 struct NoAlloc;
 
 unsafe impl AltAllocator for NoAlloc {
@@ -181,16 +187,266 @@ fn extend_from_slice_fail() {
     }
 }
 
+#[test]
+fn reserve_checked() {
+    let mut arr = FlexArr::<u32, NoAlloc, u8>::new_in(NoAlloc);
+    assert_eq!(arr.reserve_checked(0), Ok(()));
+
+    // Even though only 3 were requested the growth strategy asks for at least 8.
+    let expected = Layout::array::<u32>(8).unwrap();
+    assert_eq!(arr.reserve_checked(3), Err(ReserveError::AllocFailed(expected)));
+
+    let mut arr = FlexArr::<(), NoAlloc, u8>::new_in(NoAlloc);
+    for _ in 0..250 {
+        arr.push(()).unwrap();
+    }
+    assert_eq!(arr.reserve_checked(5), Ok(()));
+    assert_eq!(arr.reserve_checked(6), Err(ReserveError::CapacityOverflow));
+}
+
+/// Note: This test assumes usize is smaller than u128
+/// Likely, true assumption for most any architectures.
+#[test]
+fn try_index() {
+    let mut arr = FlexArr::<(), NoAlloc, u8>::new_in(NoAlloc);
+    arr.push(()).unwrap();
+    assert_eq!(arr.try_index(0), Ok(&()));
+    assert_eq!(arr.try_index_mut(0), Ok(&mut ()));
+    assert_eq!(arr.try_index(1).unwrap_err().reason(), ErrorReason::IndexOutOfBounds);
+    assert_eq!(
+        arr.try_index_mut(1).unwrap_err().reason(),
+        ErrorReason::IndexOutOfBounds
+    );
+
+    let data = [(); usize::MAX];
+    let mut arr = FlexArr::<(), NoAlloc, u128>::new_in(NoAlloc);
+    arr.extend_from_slice(&data).unwrap();
+    arr.push(()).unwrap();
+    arr.push(()).unwrap();
+
+    let index = usize::MAX as u128 + 1;
+    assert_eq!(arr.try_index(index).unwrap_err().reason(), ErrorReason::UsizeOverflow);
+    assert_eq!(
+        arr.try_index_mut(index).unwrap_err().reason(),
+        ErrorReason::UsizeOverflow
+    );
+    assert_eq!(
+        arr.try_index(index + 1).unwrap_err().reason(),
+        ErrorReason::IndexOutOfBounds
+    );
+}
+
+#[test]
+fn shrink_without_allocation() {
+    // A zero sized type never allocates, so shrinking never reaches the allocator.
+    let mut arr = FlexArr::<(), NoAlloc>::new_in(NoAlloc);
+    for _ in 0..10 {
+        arr.push(()).unwrap();
+    }
+    arr.truncate(2);
+    assert!(arr.shrink_to_fit().is_ok());
+    assert!(arr.shrink_to(0).is_ok());
+    assert!(arr.clear_and_shrink(0).is_ok());
+    assert!(arr.truncate_and_maybe_shrink(0).is_ok());
+    assert_eq!(arr.capacity(), u32::MAX);
+
+    // Nothing has been allocated yet, so there is nothing to deallocate.
+    let mut arr = FlexArr::<u64, NoAlloc, u8>::new_in(NoAlloc);
+    assert!(arr.shrink_to_fit().is_ok());
+    assert!(arr.shrink_to(0).is_ok());
+    assert!(arr.shrink_to(8).is_ok());
+    assert!(arr.clear_and_shrink(0).is_ok());
+    assert!(arr.truncate_and_maybe_shrink(0).is_ok());
+    assert_eq!(arr.capacity(), 0);
+    assert_eq!(arr.as_ptr(), dangling_mut::<u64>());
+}
+
+#[test]
+fn reserve_near_max() {
+    // Zero sized types always have a capacity of `L::MAX_VALUE`.
+    let mut arr = FlexArr::<(), NoAlloc, u8>::new_in(NoAlloc);
+    for _ in 0..(u8::MAX - 1) {
+        arr.push(()).unwrap();
+    }
+    assert!(arr.reserve(0).is_ok());
+    assert!(arr.reserve(1).is_ok());
+    let err = arr.reserve(2).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+    let err = arr.reserve(u8::MAX).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+    arr.push(()).unwrap();
+    assert!(arr.reserve(0).is_ok());
+    let err = arr.reserve(1).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+    // Nothing is allocated so anything more than zero has to try to grow.
+    let mut arr = FlexArr::<u32, NoAlloc, u8>::new_in(NoAlloc);
+    assert!(arr.reserve(0).is_ok());
+    let err = arr.reserve(1).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::AllocFailure);
+}
+
+#[test]
+fn with_capacity_zero() {
+    // `NoAlloc` fails every allocation, so this only works if nothing is allocated.
+    let arr = FlexArr::<u64, NoAlloc>::with_capacity_in(NoAlloc, 0).unwrap();
+    assert_eq!(arr.capacity(), 0);
+    assert_eq!(arr.len(), 0);
+    assert_eq!(arr.as_ptr(), dangling_mut::<u64>());
+
+    let arr = FlexArr::<(), NoAlloc, u8>::with_capacity_in(NoAlloc, 0).unwrap();
+    assert_eq!(arr.capacity(), u8::MAX);
+
+    // Anything more than zero does ask the allocator.
+    let err = FlexArr::<u64, NoAlloc>::with_capacity_in(NoAlloc, 1).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::AllocFailure);
+}
+
+#[test]
+fn alloc_failure_layout() {
+    // The common error stays a single byte, so the layout is only reported by `reserve_checked()`.
+    assert_eq!(size_of::<FlexArrErr>(), 1);
+    assert_eq!(size_of::<FlexArrResult<()>>(), 1);
+
+    let mut arr: FlexArr<u64, NoAlloc, u16> = FlexArr::new_in(NoAlloc);
+    let err = arr.reserve_exact(10).unwrap_err();
+    assert_eq!(err.reason(), ErrorReason::AllocFailure);
+    let Err(ReserveError::AllocFailed(layout)) = arr.reserve_checked(u16::MAX) else {
+        panic!("The allocation should have failed!");
+    };
+    assert_eq!(layout.size(), u16::MAX as usize * size_of::<u64>());
+    assert_eq!(layout.align(), align_of::<u64>());
+}
+
+#[test]
+fn is_zst() {
+    assert!(FlexArr::<(), NoAlloc>::is_zst());
+    assert!(FlexArr::<[u64; 0], NoAlloc, u8>::is_zst());
+    assert!(!FlexArr::<u8, NoAlloc>::is_zst());
+
+    // The zero sized path never touches the allocator.
+    let mut arr = FlexArr::<(), NoAlloc, u8>::new_in(NoAlloc);
+    assert_eq!(arr.capacity(), u8::MAX);
+    arr.reserve(u8::MAX).unwrap();
+    arr.push(()).unwrap();
+    arr.shrink_to_fit().unwrap();
+    assert_eq!(arr.capacity(), u8::MAX);
+    let (_, len, cap, _) = arr.into_parts();
+    assert_eq!((len, cap), (1, u8::MAX));
+}
+
+#[test]
+fn truncate_wide_zst() {
+    let mut arr = FlexArr::<(), NoAlloc, u128>::new_in(NoAlloc);
+    let big: u128 = 1 << 40;
+    // Pushing this many would take far too long, and zero sized values need no writes.
+    unsafe { arr.set_len(big) };
+
+    arr.truncate(u128::MAX);
+    assert_eq!(arr.len(), big);
+    arr.truncate(big);
+    assert_eq!(arr.len(), big);
+    arr.truncate(big - 1);
+    assert_eq!(arr.len(), big - 1);
+    arr.truncate(5);
+    assert_eq!(arr.len(), 5);
+    arr.truncate(0);
+    assert!(arr.is_empty());
+    arr.truncate(0);
+    assert!(arr.is_empty());
+}
+
+/// Note: This test assumes usize is smaller than u128
+#[test]
+fn keep_last_wide_zst() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    struct Zst;
+    impl Drop for Zst {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Lengths past what a usize can hold, set directly since pushing would take far too long.
+    let big: u128 = (usize::MAX as u128) + 10;
+    let mut arr = FlexArr::<Zst, NoAlloc, u128>::new_in(NoAlloc);
+    unsafe { arr.set_len(big) };
+    arr.keep_last(big);
+    assert_eq!(arr.len(), big);
+    arr.keep_last(big - 3);
+    assert_eq!(arr.len(), big - 3);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+    arr.keep_last(usize::MAX as u128);
+    assert_eq!(arr.len(), usize::MAX as u128);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+    // Dropping the rest one by one would take far too long.
+    unsafe { arr.set_len(2) };
+    drop(arr);
+    assert_eq!(DROPS.load(Ordering::Relaxed), 12);
+
+    // Without drop glue only the length changes, however many are removed.
+    let mut arr = FlexArr::<(), NoAlloc, u128>::new_in(NoAlloc);
+    unsafe { arr.set_len(u128::MAX) };
+    arr.keep_last(5);
+    assert_eq!(arr.len(), 5);
+}
+
+#[test]
+fn new_in_const() {
+    const EMPTY: FlexArr<u32, NoAlloc, u16> = FlexArr::new_in(NoAlloc);
+    const LEN: u16 = {
+        let arr = FlexArr::<u64, NoAlloc, u16>::new_in(NoAlloc);
+        let len = arr.len();
+        core::mem::forget(arr);
+        len
+    };
+    assert_eq!(LEN, 0);
+
+    let mut arr = EMPTY;
+    assert!(arr.is_empty());
+    assert_eq!(arr.capacity(), 0);
+    assert!(arr.push(1).is_err());
+}
+
+/// Note: This test assumes usize is smaller than u128
+#[test]
+fn remove_wide_zst() {
+    let mut arr = FlexArr::<(), NoAlloc, u128>::new_in(NoAlloc);
+    let len = usize::MAX as u128 + 3;
+    // Pushing this many would take far too long, and zero sized values need no writes.
+    unsafe { arr.set_len(len) };
+
+    // These indices would truncate to small values as a usize.
+    let high = usize::MAX as u128 + 1;
+    assert_eq!(arr.remove(high), Some(()));
+    assert_eq!(arr.len(), len - 1);
+    assert_eq!(arr.swap_remove(high), Some(()));
+    assert_eq!(arr.len(), len - 2);
+    assert_eq!(arr.remove(high), None);
+    assert_eq!(arr.swap_remove(high), None);
+    assert_eq!(arr.remove(0), Some(()));
+    assert_eq!(arr.swap_remove(usize::MAX as u128 - 1), Some(()));
+    assert_eq!(arr.len(), len - 4);
+}
+
 #[cfg(feature = "std_alloc")]
 mod std_alloc {
     use core::cell::Cell;
     use core::panic;
     use std::string::String;
     use std::string::ToString;
+    use std::vec::Vec;
 
     use super::*;
+    use crate::SmallFlexArr;
     use crate::alloc::Global;
+    use crate::types::CollectError;
+    use crate::types::CompactPolicy;
+    use crate::types::LengthType;
+    use crate::types::ReserveInfo;
 
+    #[derive(Debug, Clone)]
     struct AllocCount(u8, Cell<u8>);
 
     impl AllocCount {
@@ -300,11 +556,12 @@ mod std_alloc {
             assert_eq!(e.reason(), ErrorReason::LayoutFailure);
         }
 
+        // The size in bytes does not fit into the `usize` length type either.
         let massive = (usize::MAX / 256) + 1;
         let ret = FlexArr::<[u8; 256], Global, usize>::with_capacity_in(Global, massive);
         assert!(ret.is_err());
         if let Err(e) = ret {
-            assert_eq!(e.reason(), ErrorReason::UsizeOverflow);
+            assert_eq!(e.reason(), ErrorReason::CapacityOverflow);
         }
 
         let massive = ((isize::MAX / 256) + 1) as usize;
@@ -315,6 +572,59 @@ mod std_alloc {
         }
     }
 
+    macro_rules! overflow_per_length_type {
+        ($name:ident, $len:ty, $layout_fail:expr, $usize_fail:expr, $usize_reason:expr) => {
+            #[test]
+            #[cfg(target_pointer_width = "64")]
+            fn $name() {
+                // The length plus what is reserved does not fit into the length type.
+                let mut arr = FlexArr::<u8, Global, $len>::new();
+                arr.push(1).unwrap();
+                let err = arr.reserve(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+                let err = arr.reserve_exact(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+                // The size in bytes fits into a usize, but is over `isize::MAX`.
+                let mut arr = FlexArr::<[u8; $layout_fail], Global, $len>::new();
+                let err = arr.reserve_exact(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+                let err = arr.reserve(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+
+                // The size in bytes does not even fit into a usize. When the length type is
+                // as wide as a usize that is caught as a capacity overflow first.
+                let mut arr = FlexArr::<[u8; $usize_fail], Global, $len>::new();
+                let err = arr.reserve_exact(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), $usize_reason);
+                let err = arr.reserve(<$len>::MAX).unwrap_err();
+                assert_eq!(err.reason(), $usize_reason);
+            }
+        };
+    }
+
+    overflow_per_length_type!(overflow_u8, u8, 1 << 56, 1 << 57, ErrorReason::UsizeOverflow);
+    overflow_per_length_type!(overflow_u16, u16, 1 << 48, 1 << 49, ErrorReason::UsizeOverflow);
+    overflow_per_length_type!(overflow_u32, u32, 1 << 32, 1 << 33, ErrorReason::UsizeOverflow);
+    overflow_per_length_type!(overflow_u64, u64, 1, 2, ErrorReason::CapacityOverflow);
+
+    // `u128::MAX` can never be converted to a usize, so it needs its own cases.
+    #[test]
+    fn overflow_u128() {
+        let mut arr = FlexArr::<u8, Global, u128>::new();
+        arr.push(1).unwrap();
+        let err = arr.reserve(u128::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        let err = arr.reserve_exact(u128::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+        let mut arr = FlexArr::<u8, Global, u128>::new();
+        let err = arr.reserve_exact((isize::MAX as u128) + 1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+        let err = arr.reserve_exact((usize::MAX as u128) + 1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::UsizeOverflow);
+    }
+
     #[test]
     fn add_slice() {
         let data: [u8; 5] = [1, 2, 3, 4, 5];
@@ -385,7 +695,7 @@ mod std_alloc {
         assert!(arr.push(()).is_ok());
 
         let index = usize::MAX as u128 + 1;
-        let _ = arr[index];
+        let _ = &arr[index];
     }
 
     #[test]
@@ -636,4 +946,2717 @@ mod std_alloc {
 
         assert_eq!(ret.reason(), ErrorReason::IndexOutOfBounds);
     }
+
+    #[test]
+    fn windows_and_chunks() {
+        let mut arr = FlexArr::<u8, Global, u16>::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+
+        let mut windows = arr.windows(2);
+        let win = windows.next().unwrap();
+        assert_eq!(win.len(), 2u16);
+        assert_eq!(win, &[1, 2]);
+        let win = windows.next().unwrap();
+        assert_eq!(win[1u16], 3);
+        assert_eq!(win, &[2, 3]);
+        assert!(windows.next().is_none());
+        assert_eq!(arr.windows(4).count(), 0);
+
+        let mut chunks = arr.chunks(2);
+        assert_eq!(chunks.next().unwrap(), &[1, 2]);
+        let last = chunks.next().unwrap();
+        assert_eq!(last.len(), 1u16);
+        assert_eq!(last.get(0), Some(&3));
+        assert_eq!(last.get(1), None);
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    fn try_sort_by_cached_key() {
+        let mut arr = FlexArr::<String>::new();
+        arr.push("three".to_string()).unwrap();
+        arr.push("a".to_string()).unwrap();
+        arr.push("bb".to_string()).unwrap();
+        arr.push("cc".to_string()).unwrap();
+        arr.push("".to_string()).unwrap();
+
+        arr.try_sort_by_cached_key(|s| s.len()).unwrap();
+        assert_eq!(arr.as_slice(), ["", "a", "bb", "cc", "three"]);
+
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.extend_from_slice(&[3, 2, 1]).unwrap();
+        let Err(e) = arr.try_sort_by_cached_key(|x| *x) else {
+            panic!("The key buffer should not have been allocated!");
+        };
+        assert_eq!(e.reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr.as_slice(), [3, 2, 1]);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut arr = FlexArr::<u8>::new();
+        arr.extend_from_slice(&[1, 1, 1, 2]).unwrap();
+        assert_eq!(arr.dedup_count(), 2);
+        assert_eq!(arr.as_slice(), [1, 2]);
+        assert_eq!(arr.dedup_count(), 0);
+
+        let mut arr = FlexArr::<String, Global, u16>::new();
+        for s in ["a", "a", "b", "c", "c", "c", "a"] {
+            arr.push(s.to_string()).unwrap();
+        }
+        arr.dedup();
+        assert_eq!(arr.as_slice(), ["a", "b", "c", "a"]);
+
+        arr.dedup_by_key(|s| s == "a");
+        assert_eq!(arr.as_slice(), ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn reserve_pow2() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.reserve_pow2(5).unwrap();
+        assert_eq!(arr.capacity(), 8);
+        assert!(arr.capacity().is_power_of_two());
+
+        arr.extend_from_slice(&[0; 8]).unwrap();
+        arr.reserve_pow2(1).unwrap();
+        assert_eq!(arr.capacity(), 16);
+
+        // Saturates instead of overflowing the length type.
+        let mut arr = FlexArr::<u8, Global, u8>::new();
+        arr.push(1).unwrap();
+        arr.reserve_pow2(200).unwrap();
+        assert_eq!(arr.capacity(), u8::MAX);
+
+        let Err(e) = arr.reserve_pow2(255) else {
+            panic!("Reserve should have overflowed!");
+        };
+        assert_eq!(e.reason(), ErrorReason::CapacityOverflow);
+
+        let mut arr = FlexArr::<(), Global, u8>::new();
+        arr.reserve_pow2(200).unwrap();
+        assert_eq!(arr.capacity(), u8::MAX);
+    }
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    struct Probe<T>(core::marker::PhantomData<T>);
+
+    // Method resolution will pick `IsSendSync` when the bounds hold and otherwise fall
+    // back to the auto-referenced `NotSendSync` impl. That way `!Send` can be checked.
+    trait IsSendSync {
+        fn send_sync(&self) -> (bool, bool) {
+            return (true, true);
+        }
+    }
+    impl<T: Send + Sync> IsSendSync for Probe<T> {}
+
+    trait NotSendSync {
+        fn send_sync(&self) -> (bool, bool) {
+            return (false, false);
+        }
+    }
+    impl<T> NotSendSync for &Probe<T> {}
+
+    #[test]
+    fn send_sync() {
+        assert_send::<FlexArr<u8, Global, u32>>();
+        assert_sync::<FlexArr<u8, Global, u32>>();
+        assert_send::<FlexArr<String, Global, u8>>();
+        assert_sync::<FlexArr<String, Global, u8>>();
+
+        let probe = &Probe::<FlexArr<u8>>(core::marker::PhantomData);
+        assert_eq!(probe.send_sync(), (true, true));
+
+        let probe = &Probe::<FlexArr<std::rc::Rc<u8>>>(core::marker::PhantomData);
+        assert_eq!(probe.send_sync(), (false, false));
+
+        // The allocator matters too.
+        let probe = &Probe::<FlexArr<u8, AllocCount>>(core::marker::PhantomData);
+        assert_eq!(probe.send_sync(), (false, false));
+    }
+
+    #[test]
+    fn try_collect_in() {
+        let drops = Cell::new(0u32);
+        let src = [
+            Ok(DropCounter::new(0, &drops)),
+            Ok(DropCounter::new(1, &drops)),
+            Err(()),
+            Ok(DropCounter::new(2, &drops)),
+        ];
+        let ret = FlexArr::<DropCounter, Global>::try_collect_in(src, Global);
+        let Err(e) = ret else {
+            panic!("The source error should have been returned!");
+        };
+        assert_eq!(e, CollectError::Source(()));
+        // Both collected elements were dropped, and so was the one never reached.
+        assert_eq!(drops.get(), 3);
+
+        let src = [Ok::<u8, ()>(1), Ok(2), Ok(3)];
+        let arr = FlexArr::<u8>::try_collect_in(src, Global).unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+
+        let ret = FlexArr::<u8, NoAlloc>::try_collect_in([Ok::<u8, ()>(1)], NoAlloc);
+        let Err(CollectError::Array(e)) = ret else {
+            panic!("The allocation should have failed!");
+        };
+        assert_eq!(e.reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn clear_and_shrink() {
+        let mut arr = FlexArr::<String>::new();
+        for i in 0..100 {
+            arr.push(i.to_string()).unwrap();
+        }
+        arr.clear_and_shrink(4).unwrap();
+        assert!(arr.is_empty());
+        assert!(arr.capacity() <= 4);
+
+        arr.push("Hello".to_string()).unwrap();
+        assert_eq!(arr[0], "Hello");
+
+        arr.clear_and_shrink(0).unwrap();
+        assert_eq!(arr.capacity(), 0);
+        assert_eq!(arr.as_ptr() as usize % align_of::<String>(), 0);
+
+        arr.push("There".to_string()).unwrap();
+        assert_eq!(arr[0], "There");
+    }
+
+    #[test]
+    fn from_iter_rev_in() {
+        let arr = FlexArr::<u8>::from_iter_rev_in(0..5, Global).unwrap();
+        assert_eq!(arr.as_slice(), [4, 3, 2, 1, 0]);
+
+        let arr = FlexArr::<u8>::from_iter_rev_in(0..0, Global).unwrap();
+        assert!(arr.is_empty());
+
+        let ret = FlexArr::<u16, Global, u8>::from_iter_rev_in(0..=255u16, Global);
+        let Err(e) = ret else {
+            panic!("256 items should not fit into a u8 length!");
+        };
+        assert_eq!(e.reason(), ErrorReason::CapacityOverflow);
+    }
+
+    #[test]
+    fn get_range() {
+        let mut arr = FlexArr::<u8, Global, u16>::new();
+        arr.extend_from_slice(&[0, 1, 2, 3, 4]).unwrap();
+
+        assert_eq!(arr.get_range(1..3).unwrap(), &[1, 2]);
+        assert_eq!(arr.get_range(3..).unwrap(), &[3, 4]);
+        assert_eq!(arr.get_range(..2).unwrap(), &[0, 1]);
+        assert_eq!(arr.get_range(..).unwrap(), &[0, 1, 2, 3, 4]);
+        assert_eq!(arr.get_range(1..=3).unwrap(), &[1, 2, 3]);
+        assert_eq!(arr.get_range(5..).unwrap().len(), 0);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let inverted = arr.get_range(3..1);
+        assert!(inverted.is_none());
+        assert!(arr.get_range(4..6).is_none());
+        assert!(arr.get_range(6..).is_none());
+        assert!(arr.get_range(..=u16::MAX).is_none());
+
+        let view = arr.get_range_mut(1..3).unwrap();
+        view[0u16] = 10;
+        view[1u16] = 20;
+        assert_eq!(arr.as_slice(), [0, 10, 20, 3, 4]);
+        assert!(arr.get_range_mut(2..9).is_none());
+    }
+
+    #[test]
+    fn growth_pattern() {
+        let mut arr = FlexArr::<u64>::new();
+        let mut caps = std::vec::Vec::new();
+        for i in 0..100 {
+            arr.push(i).unwrap();
+            if caps.last() != Some(&arr.capacity()) {
+                caps.push(arr.capacity());
+            }
+        }
+        // Starts at 8 then grows by 1.5x each time.
+        assert_eq!(caps, [8, 12, 18, 27, 40, 60, 90, 135]);
+    }
+
+    #[test]
+    fn push_pop_front() {
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.reserve(4).unwrap();
+        arr.push(2).unwrap();
+        arr.push_front(1).unwrap();
+        arr.push_front(0).unwrap();
+        assert_eq!(arr.as_slice(), [0, 1, 2]);
+
+        // The single allocation was used up above, so these would fail if reallocating.
+        for i in 0..100 {
+            arr.push_front(i).unwrap();
+            assert_eq!(arr.pop_front(), Some(i));
+        }
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+        assert_eq!(arr.pop_front(), Some(0));
+        assert_eq!(arr.pop_front(), Some(1));
+        assert_eq!(arr.pop_front(), Some(2));
+        assert_eq!(arr.pop_front(), None);
+    }
+
+    #[test]
+    fn truncate_and_maybe_shrink() {
+        let mut arr = FlexArr::<u32>::with_capacity(1000).unwrap();
+        for i in 0..1000 {
+            arr.push(i).unwrap();
+        }
+        arr.truncate_and_maybe_shrink(800).unwrap();
+        assert_eq!(arr.len(), 800);
+        assert_eq!(arr.capacity(), 1000);
+
+        arr.truncate_and_maybe_shrink(10).unwrap();
+        assert_eq!(arr.len(), 10);
+        assert_eq!(arr.capacity(), 10);
+        for i in 0..10 {
+            assert_eq!(arr[i], i);
+        }
+
+        arr.reserve_exact(90).unwrap();
+        arr.shrink_to(50).unwrap();
+        assert_eq!(arr.capacity(), 50);
+        arr.shrink_to(0).unwrap();
+        assert_eq!(arr.capacity(), 10);
+    }
+
+    #[test]
+    fn reserve_with_retry() {
+        struct Gated<'a>(&'a Cell<bool>);
+
+        unsafe impl AltAllocator for Gated<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                if !self.0.get() {
+                    return Err(AllocError);
+                }
+                return Global.allocate(layout);
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                unsafe { Global.deallocate(ptr, layout) };
+            }
+        }
+
+        let open = Cell::new(false);
+        let mut arr = FlexArr::<u8, Gated>::new_in(Gated(&open));
+
+        let mut calls = 0;
+        let ret = arr.reserve_with_retry(16, || {
+            calls += 1;
+            return false;
+        });
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::AllocFailure);
+        assert_eq!(calls, 1);
+
+        // Gives up after the retry limit.
+        calls = 0;
+        let ret = arr.reserve_with_retry(16, || {
+            calls += 1;
+            return true;
+        });
+        assert!(ret.is_err());
+        assert_eq!(calls, FlexArr::<u8, Gated>::RESERVE_RETRY_LIMIT);
+
+        // "Frees" memory on the first failure so the second attempt works.
+        calls = 0;
+        let ret = arr.reserve_with_retry(16, || {
+            calls += 1;
+            open.set(true);
+            return true;
+        });
+        assert!(ret.is_ok());
+        assert_eq!(calls, 1);
+        assert!(arr.capacity() >= 16);
+    }
+
+    fn hash_of<H: core::hash::Hash + ?Sized>(val: &H) -> u64 {
+        use core::hash::Hasher;
+        let mut hasher = std::hash::DefaultHasher::new();
+        val.hash(&mut hasher);
+        return hasher.finish();
+    }
+
+    #[test]
+    fn eq_hash_across_length_types() {
+        let data = b"some bytes";
+        let mut a = FlexArr::<u8, Global, u16>::new();
+        let mut b = FlexArr::<u8, Global, u32>::new();
+        let mut c = FlexArr::<u8, AllocCount, u8>::new_in(AllocCount::new(1));
+        a.extend_from_slice(data).unwrap();
+        b.extend_from_slice(data).unwrap();
+        c.extend_from_slice(data).unwrap();
+
+        assert!(a.eq_contents(&b));
+        assert!(b.eq_contents(&c));
+        assert!(a == b);
+        assert!(c == a);
+        assert_eq!(&a, data);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_eq!(hash_of(&a), hash_of(&c));
+
+        b.push(0).unwrap();
+        assert!(!a.eq_contents(&b));
+        assert!(a != b);
+        assert_ne!(hash_of(&a), hash_of(&b));
+    }
+
+    #[test]
+    fn zeroed_in() {
+        let arr = FlexArr::<u8>::zeroed_in(Global, 16).unwrap();
+        assert_eq!(arr.len(), 16);
+        assert!(arr.iter().all(|b| *b == 0));
+
+        let arr = FlexArr::<u8>::zeroed_in(Global, 0).unwrap();
+        assert_eq!(arr.len(), 0);
+        assert_eq!(arr.capacity(), 0);
+
+        let ret = FlexArr::<u8, NoAlloc>::zeroed_in(NoAlloc, 4);
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr = FlexArr::<u8>::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        arr.reverse();
+        assert_eq!(arr, [5, 4, 3, 2, 1]);
+
+        arr.reverse();
+        arr.reverse_range(1..4);
+        assert_eq!(arr, [1, 4, 3, 2, 5]);
+
+        arr.reverse_range(3..);
+        assert_eq!(arr, [1, 4, 3, 5, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reverse_range_out_of_bounds() {
+        let mut arr = FlexArr::<u8>::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        arr.reverse_range(1..4);
+    }
+
+    #[test]
+    fn retain() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5, 6]).unwrap();
+        arr.retain(|x| x % 3 != 0);
+        assert_eq!(arr, [1, 2, 4, 5]);
+
+        arr.retain_mut(|x| {
+            *x *= 10;
+            return *x > 10;
+        });
+        assert_eq!(arr, [20, 40, 50]);
+
+        let mut arr = FlexArr::<String>::new();
+        for i in 1..=4 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let removed = arr.retain_and_collect(|s| s.parse::<u32>().unwrap() % 2 == 1).unwrap();
+        assert_eq!(arr.as_slice(), ["1", "3"]);
+        assert_eq!(removed.as_slice(), ["2", "4"]);
+        assert_eq!(removed.capacity(), 2);
+
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.extend_from_slice(&[1, 2, 3, 4]).unwrap();
+        let ret = arr.retain_and_collect(|x| x % 2 == 1);
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn partition_in() {
+        let mut arr = FlexArr::<String>::new();
+        for i in 1..=5 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let ptr = arr.as_ptr();
+        let (odd, even) = arr
+            .partition_in(|s| s.parse::<u32>().unwrap() % 2 == 1, Global)
+            .unwrap();
+        assert_eq!(odd.as_slice(), ["1", "3", "5"]);
+        assert_eq!(even.as_slice(), ["2", "4"]);
+        // The true side kept the original buffer.
+        assert_eq!(odd.as_ptr(), ptr);
+
+        let drops = Cell::new(0u32);
+        let arr = drop_counter_arr(6, &drops);
+        assert_panics(|| {
+            let _ = arr.partition_in(
+                |d| {
+                    if d.id == 4 {
+                        panic!("Oops");
+                    }
+                    return d.id % 2 == 0;
+                },
+                Global,
+            );
+        });
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[derive(Debug)]
+    struct RoundUp16;
+
+    impl RoundUp16 {
+        fn round(layout: Layout) -> Layout {
+            let size = layout.size().next_multiple_of(16);
+            return Layout::from_size_align(size, layout.align()).unwrap();
+        }
+    }
+
+    unsafe impl AltAllocator for RoundUp16 {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            return Global.allocate(Self::round(layout));
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, Self::round(layout)) };
+        }
+    }
+
+    #[test]
+    fn adopt_allocator_capacity() {
+        let arr = FlexArr::<u8, RoundUp16>::with_capacity_in(RoundUp16, 10).unwrap();
+        assert_eq!(arr.capacity(), 16);
+
+        let mut arr = FlexArr::<u32, RoundUp16>::with_capacity_in(RoundUp16, 10).unwrap();
+        assert_eq!(arr.capacity(), 12);
+        for i in 0..12 {
+            arr.push(i).unwrap();
+        }
+        assert_eq!(arr.capacity(), 12);
+        arr.push(12).unwrap();
+        // 1.5 * 12 = 18 elements is 72 bytes which is rounded to 80.
+        assert_eq!(arr.capacity(), 20);
+        assert!(arr.iter().copied().eq(0..13));
+
+        // The adopted capacity is clamped to what the length type can hold.
+        let arr = FlexArr::<u8, RoundUp16, u8>::with_capacity_in(RoundUp16, 250).unwrap();
+        assert_eq!(arr.capacity(), u8::MAX);
+
+        // Allocators that return exactly what was requested are unchanged.
+        let arr = FlexArr::<u8>::with_capacity(10).unwrap();
+        assert_eq!(arr.capacity(), 10);
+    }
+
+    #[test]
+    fn insert_many() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[0, 9]).unwrap();
+        arr.insert_many(1, 3..6).unwrap();
+        assert_eq!(arr.as_slice(), [0, 3, 4, 5, 9]);
+        arr.insert_many(5, [10, 11]).unwrap();
+        arr.insert_many(0, core::iter::empty()).unwrap();
+        assert_eq!(arr.as_slice(), [0, 3, 4, 5, 9, 10, 11]);
+        let err = arr.insert_many(8, [1]).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::IndexOutOfBounds);
+
+        let mut arr = FlexArr::<String>::new();
+        arr.push("a".to_string()).unwrap();
+        arr.push("z".to_string()).unwrap();
+        assert_panics(|| {
+            let items = (0..4).map(|i| {
+                if i == 2 {
+                    panic!("Oops");
+                }
+                return i.to_string();
+            });
+            let _ = arr.insert_many(1, items);
+        });
+        assert_eq!(arr.as_slice(), ["a", "0", "1", "z"]);
+    }
+
+    #[test]
+    fn resize() {
+        let mut arr = FlexArr::<String>::new();
+        arr.resize(3, "a".to_string()).unwrap();
+        assert_eq!(arr.as_slice(), ["a", "a", "a"]);
+        arr.resize(1, "b".to_string()).unwrap();
+        assert_eq!(arr.as_slice(), ["a"]);
+
+        let mut next = 0;
+        arr.resize_with(4, || {
+            next += 1;
+            return next.to_string();
+        })
+        .unwrap();
+        assert_eq!(arr.as_slice(), ["a", "1", "2", "3"]);
+        arr.resize_with(0, || unreachable!()).unwrap();
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn resize_overflow_wide_length() {
+        let calls = Cell::new(0u32);
+        let make = || {
+            calls.set(calls.get() + 1);
+            return 0u8;
+        };
+
+        let massive: u128 = (usize::MAX as u128) + 1;
+        let mut arr = FlexArr::<u8, Global, u128>::new();
+        let err = arr.resize(massive, 0).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::UsizeOverflow);
+        let err = arr.resize_with(massive, make).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::UsizeOverflow);
+
+        let massive: u128 = (isize::MAX as u128) + 1;
+        let err = arr.resize(massive, 0).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+        let err = arr.resize_with(massive, make).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+
+        let massive = ((isize::MAX / 256) + 1) as u128;
+        let mut arr = FlexArr::<[u8; 256], Global, u128>::new();
+        let err = arr.resize_with(massive, || [0; 256]).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+        assert_eq!(arr.len(), 0);
+
+        // Zero sized types never allocate, but the length must still fit a usize.
+        let massive: u128 = (usize::MAX as u128) + 1;
+        let mut arr = FlexArr::<(), Global, u128>::new();
+        let err = arr.resize_with(massive, || calls.set(calls.get() + 1)).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::UsizeOverflow);
+        assert_eq!(arr.len(), 0);
+
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn try_sort() {
+        let mut state = 0x2545_f491u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            return state;
+        };
+
+        for len in [0, 1, 2, 20, 21, 100, 5000] {
+            let mut arr = FlexArr::<u32>::new();
+            let mut expected = Vec::new();
+            for _ in 0..len {
+                let val = next() % 1000;
+                arr.push(val).unwrap();
+                expected.push(val);
+            }
+            arr.try_sort().unwrap();
+            expected.sort();
+            assert_eq!(arr.as_slice(), expected.as_slice());
+        }
+
+        // Equal keys keep their original order.
+        let mut arr = FlexArr::<(u8, u32)>::new();
+        for i in 0..200 {
+            arr.push(((next() % 4) as u8, i)).unwrap();
+        }
+        arr.try_sort_by(|a, b| a.0.cmp(&b.0)).unwrap();
+        assert!(
+            arr.windows(2)
+                .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1))
+        );
+
+        // Short arrays never need the scratch buffer.
+        let mut arr = FlexArr::<u32, AllocCount>::with_capacity_in(AllocCount::new(1), 21).unwrap();
+        for i in (0..20).rev() {
+            arr.push(i).unwrap();
+        }
+        arr.try_sort().unwrap();
+        assert!(arr.iter().copied().eq(0..20));
+
+        // Running out of memory for the scratch buffer gives an error and leaves the array alone.
+        arr.push(0).unwrap();
+        let err = arr.try_sort().unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert!(arr.iter().copied().eq((0..20).chain([0])));
+    }
+
+    #[test]
+    fn try_sort_by_panic() {
+        let mut arr = FlexArr::<String>::new();
+        for i in (0..64).rev() {
+            arr.push(i.to_string()).unwrap();
+        }
+        let mut calls = 0;
+        assert_panics(|| {
+            let _ = arr.try_sort_by(|a, b| {
+                calls += 1;
+                if calls == 100 {
+                    panic!("Oops");
+                }
+                return a.cmp(b);
+            });
+        });
+        // Every element is still there exactly once.
+        let mut items: Vec<u32> = arr.iter().map(|s| s.parse().unwrap()).collect();
+        items.sort();
+        assert!(items.into_iter().eq(0..64));
+    }
+
+    #[test]
+    fn extend_unique() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2]).unwrap();
+        arr.extend_unique([2, 3, 3, 4]).unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3, 4]);
+        arr.extend_unique(core::iter::repeat_n(4, 10)).unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn is_sorted() {
+        let mut arr = FlexArr::<u32>::new();
+        assert!(arr.is_sorted());
+        arr.extend_from_slice(&[1, 2, 2, 5]).unwrap();
+        assert!(arr.is_sorted());
+        assert!(arr.is_sorted_by(|a, b| a <= b));
+        assert!(!arr.is_sorted_by(|a, b| a < b));
+        arr.push(0).unwrap();
+        assert!(!arr.is_sorted());
+    }
+
+    #[test]
+    fn with_exact_capacity_from_layout() {
+        let layout = Layout::array::<u16>(32).unwrap();
+        let buf = Global.allocate(layout).unwrap();
+        let mut arr = unsafe { FlexArr::<u16>::with_exact_capacity_from_layout(buf, Global) }.unwrap();
+        assert_eq!(arr.len(), 0);
+        assert_eq!(arr.capacity(), 32);
+        let ptr = arr.as_ptr();
+        for i in 0..32 {
+            arr.push(i).unwrap();
+        }
+        assert_eq!(arr.as_ptr(), ptr);
+        assert!(arr.iter().copied().eq(0..32));
+
+        // The capacity does not fit into a `u8`.
+        let layout = Layout::array::<u16>(256).unwrap();
+        let buf = Global.allocate(layout).unwrap();
+        let ret = unsafe { FlexArr::<u16, Global, u8>::with_exact_capacity_from_layout(buf, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::CapacityOverflow);
+
+        // Not enough room for even one element.
+        let small = NonNull::slice_from_raw_parts(buf.cast::<u8>(), 1);
+        let ret = unsafe { FlexArr::<u16>::with_exact_capacity_from_layout(small, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::LayoutFailure);
+
+        let ret = unsafe { FlexArr::<()>::with_exact_capacity_from_layout(buf, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::LayoutFailure);
+        unsafe { Global.deallocate(buf.cast(), layout) };
+    }
+
+    #[test]
+    fn parts_with_layout() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[repr(align(64))]
+        struct Aligned(u8);
+
+        let mut arr = FlexArr::<Aligned>::new();
+        for i in 0..5 {
+            arr.push(Aligned(i)).unwrap();
+        }
+        let cap = arr.capacity() as usize;
+        let (ptr, len, cap_l, alloc, layout) = arr.into_parts_with_layout();
+        assert_eq!(layout.align(), 64);
+        assert_eq!(layout.size(), cap * 64);
+        assert_eq!(ptr.as_ptr() as usize % 64, 0);
+
+        // A layout that does not match is rejected.
+        let wrong = Layout::from_size_align(layout.size(), 8).unwrap();
+        let ret = unsafe { FlexArr::<Aligned>::from_parts_with_layout(ptr, len, cap_l, Global, wrong) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::LayoutFailure);
+
+        let arr = unsafe { FlexArr::<Aligned>::from_parts_with_layout(ptr, len, cap_l, alloc, layout) }.unwrap();
+        assert_eq!(arr.as_slice(), [0, 1, 2, 3, 4].map(Aligned));
+
+        // Nothing allocated gives a zero sized layout.
+        let (ptr, len, cap, alloc, layout) = FlexArr::<Aligned>::new().into_parts_with_layout();
+        assert_eq!(layout.size(), 0);
+        assert_eq!(layout.align(), 64);
+        let arr = unsafe { FlexArr::<Aligned>::from_parts_with_layout(ptr, len, cap, alloc, layout) }.unwrap();
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn clone_is_tight() {
+        let mut arr = FlexArr::<String, AllocCount>::with_capacity_in(AllocCount::new(2), 100).unwrap();
+        for i in 0..5 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let copy = arr.try_clone().unwrap();
+        assert_eq!(copy.len(), 5);
+        assert_eq!(copy.capacity(), copy.len());
+        assert_eq!(arr.capacity(), 100);
+        assert_eq!(copy, arr);
+
+        let copy = arr.clone();
+        assert_eq!(copy.capacity(), 5);
+        assert_eq!(copy, arr);
+
+        // An empty array clones without allocating anything.
+        let empty = FlexArr::<String, AllocCount>::new_in(AllocCount::new(0));
+        let copy = empty.try_clone().unwrap();
+        assert_eq!(copy.capacity(), 0);
+
+        assert!(copy.is_empty());
+
+        // The allocator was cloned after using up its only allocation.
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.push(1).unwrap();
+        let err = arr.try_clone().unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn reserve_info() {
+        // `AllocCount` never grows in place so growing always moves the elements.
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(2));
+        let info = arr.reserve_info(4).unwrap();
+        assert!(info.reallocated);
+        assert_eq!(info.capacity, 8);
+
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        let info = arr.reserve_info(5).unwrap();
+        assert_eq!(
+            info,
+            ReserveInfo {
+                capacity:    8,
+                reallocated: false,
+            }
+        );
+
+        let info = arr.reserve_info(6).unwrap();
+        assert_eq!(
+            info,
+            ReserveInfo {
+                capacity:    12,
+                reallocated: true,
+            }
+        );
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+
+        let err = arr.reserve_info(10).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn append() {
+        let mut other = FlexArr::<String, AllocCount>::new_in(AllocCount::new(1));
+        for i in 0..3 {
+            other.push(i.to_string()).unwrap();
+        }
+        let ptr = other.as_ptr();
+
+        // Nothing is allocated so the buffer is taken as is.
+        let mut arr = FlexArr::<String, AllocCount>::new_in(AllocCount::new(0));
+        arr.append(&mut other).unwrap();
+        assert_eq!(arr.as_slice(), ["0", "1", "2"]);
+        assert_eq!(arr.as_ptr(), ptr);
+        assert!(other.is_empty());
+        assert_eq!(other.capacity(), 0);
+        let err = other.push("a".to_string()).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+
+        // Otherwise the elements are moved over.
+        let mut other = FlexArr::<String>::new();
+        other.push("3".to_string()).unwrap();
+        let mut arr2 = FlexArr::<String>::new();
+        arr2.push("x".to_string()).unwrap();
+        arr2.append(&mut other).unwrap();
+        assert_eq!(arr2.as_slice(), ["x", "3"]);
+        assert!(other.is_empty());
+        assert!(other.capacity() > 0);
+
+        // An empty array that already has memory copies too.
+        let mut arr3 = FlexArr::<String>::with_capacity(4).unwrap();
+        let ptr = arr3.as_ptr();
+        arr3.append(&mut arr2).unwrap();
+        assert_eq!(arr3.as_slice(), ["x", "3"]);
+        assert_eq!(arr3.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn get_range_unchecked() {
+        let mut arr = FlexArr::<u32, Global, u16>::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        for start in 0..=5u16 {
+            for end in start..=5u16 {
+                let checked = arr.get_range(start..end).unwrap().as_slice().as_ptr_range();
+                let unchecked = unsafe { arr.get_range_unchecked(start..end) };
+                assert_eq!(unchecked.as_slice().as_ptr_range(), checked);
+                assert_eq!(unchecked.len(), end - start);
+            }
+        }
+
+        let view = unsafe { arr.get_range_mut_unchecked(1..4) };
+        view[0u16] = 20;
+        view.as_mut_slice()[2] = 40;
+        assert_eq!(arr.as_slice(), [1, 20, 3, 40, 5]);
+        assert!(unsafe { arr.get_range_mut_unchecked(5..5) }.is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn get_range_unchecked_debug_assert() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        // Debug builds catch the bad range before it is used.
+        let _ = unsafe { arr.get_range_unchecked(2..4) };
+    }
+
+    #[test]
+    fn try_extend_reservations() {
+        // The exact length is known so there is a single allocation.
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(1));
+        arr.try_extend(0..256).unwrap();
+        assert!(arr.iter().copied().eq(0..256));
+        assert_eq!(arr.capacity(), 256);
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        let arr = FlexArr::<u32, AllocCount>::try_from_iter_in(0..256, AllocCount::new(1)).unwrap();
+        assert!(arr.iter().copied().eq(0..256));
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        // Without an exact length the capacity has to grow along the way.
+        let arr =
+            FlexArr::<u32, AllocCount>::try_from_iter_in((0..256).filter(|i| i % 2 == 0), AllocCount::new(20)).unwrap();
+        assert!(arr.iter().copied().eq((0..256).step_by(2)));
+        assert!(FlexArr::allocator(&arr).1.get() > 1);
+
+        // Only one allocation is allowed, so the growth fails.
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(1));
+        let err = arr.try_extend((0..256).filter(|i| i % 2 == 0)).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr.len(), 8);
+
+        let mut arr = FlexArr::<u8, Global, u8>::new();
+        let err = arr.try_extend(0..=255).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        assert!(arr.is_empty());
+
+        // Extending one exact item at a time still grows amortized.
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(u8::MAX));
+        for i in 0..1000 {
+            arr.try_extend(core::iter::once(i)).unwrap();
+        }
+        assert!(arr.iter().copied().eq(0..1000));
+        let allocs = FlexArr::allocator(&arr).1.get();
+        assert!(allocs < 20, "{allocs} allocations for 1000 single item extends");
+    }
+
+    #[test]
+    fn truncate_rev() {
+        struct Logged<'a>(u32, &'a core::cell::RefCell<Vec<u32>>);
+        impl Drop for Logged<'_> {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(self.0);
+            }
+        }
+
+        let log = core::cell::RefCell::new(Vec::new());
+        let mut arr = FlexArr::<Logged>::new();
+        for i in 0..6 {
+            arr.push(Logged(i, &log)).unwrap();
+        }
+        arr.truncate_rev(6);
+        assert!(log.borrow().is_empty());
+        arr.truncate_rev(2);
+        assert_eq!(arr.len(), 2);
+        assert_eq!(*log.borrow(), [5, 4, 3, 2]);
+
+        // `truncate()` keeps dropping from the front of the removed elements.
+        log.borrow_mut().clear();
+        for i in 2..5 {
+            arr.push(Logged(i, &log)).unwrap();
+        }
+        arr.truncate(1);
+        assert_eq!(*log.borrow(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn as_chunks() {
+        let mut arr = FlexArr::<u8>::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+        let (chunks, rest) = arr.as_chunks::<3>();
+        assert_eq!(chunks, [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(rest, [7]);
+
+        let (rest, chunks) = arr.as_rchunks::<3>();
+        assert_eq!(rest, [1]);
+        assert_eq!(chunks, [[2, 3, 4], [5, 6, 7]]);
+
+        let (chunks, rest) = arr.as_chunks_mut::<3>();
+        for chunk in chunks {
+            chunk.reverse();
+        }
+        rest[0] = 0;
+        assert_eq!(arr.as_slice(), [3, 2, 1, 6, 5, 4, 0]);
+
+        // Shorter than a chunk, and an exact multiple of it.
+        assert_eq!(arr.as_chunks::<8>(), (&[][..], arr.as_slice()));
+        assert_eq!(arr.as_rchunks::<8>(), (arr.as_slice(), &[][..]));
+        arr.pop();
+        let (rest, chunks) = arr.as_rchunks::<2>();
+        assert!(rest.is_empty());
+        assert_eq!(chunks, [[3, 2], [1, 6], [5, 4]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn as_chunks_zero() {
+        let arr = FlexArr::<u8>::new();
+        let _ = arr.as_chunks::<0>();
+    }
+
+    #[test]
+    fn extend_from() {
+        let mut arr = FlexArr::<u8>::new();
+        arr.extend_from([1, 2, 3]).unwrap();
+        let mut other = FlexArr::<u8, Global, u16>::new();
+        other.extend_from(&arr).unwrap();
+        other.extend_from(std::vec![4, 5]).unwrap();
+        other.extend_from(&arr.as_slice()[1..]).unwrap();
+        assert_eq!(other.as_slice(), [1, 2, 3, 4, 5, 2, 3]);
+        assert_eq!(arr.as_ref(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn dedup_extract() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 1, 2, 2, 2, 3]).unwrap();
+        let removed = arr.dedup_extract(|a, b| a == b).unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+        assert_eq!(removed.as_slice(), [1, 2, 2]);
+        assert_eq!(removed.capacity(), 3);
+
+        // Merge the counts of the duplicates into the element that is kept.
+        let mut arr = FlexArr::<(char, u32)>::new();
+        arr.extend_from_slice(&[('a', 1), ('a', 2), ('b', 3), ('a', 4)])
+            .unwrap();
+        let removed = arr
+            .dedup_extract(|a, b| {
+                if a.0 != b.0 {
+                    return false;
+                }
+                b.1 += a.1;
+                return true;
+            })
+            .unwrap();
+        assert_eq!(arr.as_slice(), [('a', 3), ('b', 3), ('a', 4)]);
+        assert_eq!(removed.as_slice(), [('a', 2)]);
+
+        let mut arr = FlexArr::<String>::new();
+        let removed = arr.dedup_extract(|a, b| a == b).unwrap();
+        assert!(removed.is_empty());
+
+        // A panic part way through keeps every element exactly once.
+        for s in ["a", "a", "b", "b", "c"] {
+            arr.push(s.to_string()).unwrap();
+        }
+        let mut calls = 0;
+        assert_panics(|| {
+            let _ = arr.dedup_extract(|a, b| {
+                calls += 1;
+                if calls == 3 {
+                    panic!("Oops");
+                }
+                return a == b;
+            });
+        });
+        assert_eq!(arr.as_slice(), ["a", "b", "b", "c"]);
+
+        // Not being able to allocate the output leaves the array alone.
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.extend_from_slice(&[1, 1]).unwrap();
+        let err = arr.dedup_extract(|a, b| a == b).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr.as_slice(), [1, 1]);
+    }
+
+    #[test]
+    fn small_flex_arr() {
+        let mut arr = SmallFlexArr::<u32, 4, AllocCount>::new_in(AllocCount::new(1));
+        assert_eq!(arr.capacity(), 4);
+        for i in 0..4 {
+            arr.push(i).unwrap();
+        }
+        assert!(arr.is_inline());
+        assert_eq!(arr.capacity(), 4);
+        assert_eq!(SmallFlexArr::allocator(&arr).1.get(), 0);
+
+        arr.push(4).unwrap();
+        assert!(!arr.is_inline());
+        assert!(arr.capacity() >= 5);
+        assert_eq!(SmallFlexArr::allocator(&arr).1.get(), 1);
+        assert_eq!(arr.as_slice(), [0, 1, 2, 3, 4]);
+        assert_eq!(arr.get(4), Some(&4));
+        assert_eq!(arr.get(5), None);
+        *arr.get_mut(0).unwrap() = 10;
+        assert_eq!(arr.pop(), Some(4));
+        assert_eq!(arr.len(), 4);
+        assert_eq!(arr[0], 10);
+
+        // Elements are dropped both while inline and after spilling.
+        let mut arr = SmallFlexArr::<String, 2>::new();
+        arr.push("a".to_string()).unwrap();
+        assert_eq!(arr.pop().as_deref(), Some("a"));
+        assert_eq!(arr.pop(), None);
+        arr.push("b".to_string()).unwrap();
+        let mut arr2 = SmallFlexArr::<String, 2>::default();
+        for i in 0..3 {
+            arr2.push(i.to_string()).unwrap();
+        }
+        assert_eq!(std::format!("{arr2:?}"), r#"["0", "1", "2"]"#);
+
+        // The inline capacity is clamped to the length type.
+        let arr = SmallFlexArr::<u8, 300, Global, u8>::new();
+        assert_eq!(arr.capacity(), u8::MAX);
+
+        // Zero sized types never allocate.
+        let mut arr = SmallFlexArr::<(), 2, AllocCount>::new_in(AllocCount::new(0));
+        for _ in 0..10 {
+            arr.push(()).unwrap();
+        }
+        assert_eq!(arr.len(), 10);
+    }
+
+    #[test]
+    fn small_flex_arr_api() {
+        // The inline buffer and the allocated array are not both stored.
+        assert!(size_of::<SmallFlexArr<u64, 4>>() < 4 * size_of::<u64>() + size_of::<FlexArr<u64>>());
+
+        let mut arr = SmallFlexArr::<u32, 4, AllocCount>::new_in(AllocCount::new(1));
+        arr.extend_from_slice(&[1, 3]).unwrap();
+        arr.insert(1, 2).unwrap();
+        arr.insert(0, 0).unwrap();
+        assert_eq!(arr.insert(5, 9).unwrap_err().reason(), ErrorReason::IndexOutOfBounds);
+        assert!(arr.is_inline());
+        assert_eq!(arr, [0, 1, 2, 3]);
+        assert_eq!(arr.iter().sum::<u32>(), 6);
+        for item in &mut arr {
+            *item += 1;
+        }
+        assert_eq!(arr.remove(0), Some(1));
+        assert_eq!(arr.remove(3), None);
+        assert_eq!(arr.as_slice(), [2, 3, 4]);
+
+        let copy = arr.clone();
+        assert!(copy.is_inline());
+        assert_eq!(copy, arr);
+
+        // Extending past the inline capacity spills once with room for the whole slice.
+        arr.extend_from_slice(&[5, 6, 7]).unwrap();
+        assert!(!arr.is_inline());
+        assert_eq!(SmallFlexArr::allocator(&arr).1.get(), 1);
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7]);
+        assert_ne!(arr, copy);
+        arr.insert(0, 1).unwrap();
+        assert_eq!(arr.remove(6), Some(7));
+        arr.truncate(3);
+        assert_eq!(arr, [1, 2, 3]);
+        arr.clear();
+        assert!(arr.is_empty());
+        assert!(!arr.is_inline());
+
+        // Elements are dropped by truncate and clear while inline.
+        let drops = Cell::new(0);
+        let mut arr = SmallFlexArr::<DropCounter, 4>::new();
+        for i in 0..4 {
+            arr.push(DropCounter::new(i, &drops)).unwrap();
+        }
+        arr.truncate(2);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(arr.remove(0).map(|d| d.id), Some(0));
+        assert_eq!(drops.get(), 3);
+        arr.clear();
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn reserve_zero_when_allocated() {
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(1));
+        arr.push(1).unwrap();
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        // Fill it up so there is no spare room either.
+        while arr.len() < arr.capacity() {
+            arr.push(2).unwrap();
+        }
+        assert!(arr.reserve(0).is_ok());
+        assert!(arr.reserve_exact(0).is_ok());
+        assert!(arr.reserve_pow2(0).is_ok());
+        assert!(arr.reserve_checked(0).is_ok());
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        // The budget really is used up.
+        let err = arr.reserve(1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn try_from_parts() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        let (ptr, len, cap, alloc) = arr.into_parts();
+
+        let ret = unsafe { FlexArr::<u32>::try_from_parts(ptr, cap + 1, cap, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::CapacityOverflow);
+
+        let ret = unsafe { FlexArr::<u32, Global, u64>::try_from_parts(ptr, 0, (isize::MAX / 4) as u64 + 1, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::LayoutFailure);
+        let ret = unsafe { FlexArr::<u32, Global, u64>::try_from_parts(ptr, 0, u64::MAX, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::UsizeOverflow);
+        let ret = unsafe { FlexArr::<u32, Global, u128>::try_from_parts(ptr, 0, u128::MAX, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::UsizeOverflow);
+
+        let unaligned = unsafe { NonNull::new_unchecked(ptr.as_ptr().cast::<u8>().add(1).cast::<u32>()) };
+        let ret = unsafe { FlexArr::<u32>::try_from_parts(unaligned, len, cap, Global) };
+        assert_eq!(ret.unwrap_err().reason(), ErrorReason::LayoutFailure);
+
+        let arr = unsafe { FlexArr::<u32>::try_from_parts(ptr, len, cap, alloc) }.unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn hash_ord_match_vec() {
+        let inputs: [&[u32]; 6] = [
+            &[],
+            &[0],
+            &[1, 2, 3],
+            &[1, 2],
+            &[3],
+            &[1, 2, 3, 0],
+        ];
+        for a in inputs {
+            let mut flex_a = FlexArr::<u32, Global, u8>::new();
+            flex_a.extend_from_slice(a).unwrap();
+            // Extra capacity must not change anything.
+            flex_a.reserve(20).unwrap();
+            let vec_a = a.to_vec();
+            assert_eq!(hash_of(&flex_a), hash_of(&vec_a));
+
+            for b in inputs {
+                let mut flex_b = FlexArr::<u32>::new();
+                flex_b.extend_from_slice(b).unwrap();
+                let vec_b = b.to_vec();
+                assert_eq!(flex_a.partial_cmp(&flex_b), vec_a.partial_cmp(&vec_b));
+                assert_eq!(flex_a == flex_b, vec_a == vec_b);
+
+                let mut same_type = FlexArr::<u32, Global, u8>::new();
+                same_type.extend_from_slice(b).unwrap();
+                assert_eq!(flex_a.cmp(&same_type), vec_a.cmp(&vec_b));
+            }
+        }
+
+        // Floats only have a partial order.
+        let mut nan = FlexArr::<f32>::new();
+        nan.push(f32::NAN).unwrap();
+        assert_eq!(
+            nan.partial_cmp(&nan),
+            std::vec![f32::NAN].partial_cmp(&std::vec![f32::NAN])
+        );
+
+        // Usable as keys in a sorted set.
+        let mut set = std::collections::BTreeSet::new();
+        for a in inputs {
+            let mut flex = FlexArr::<u32>::new();
+            flex.extend_from_slice(a).unwrap();
+            set.insert(flex);
+        }
+        let mut sorted = inputs.map(|a| a.to_vec());
+        sorted.sort();
+        assert!(set.iter().map(|f| f.as_slice()).eq(sorted.iter().map(|v| v.as_slice())));
+    }
+
+    #[test]
+    fn remove_first_last() {
+        let mut arr = FlexArr::<char>::new();
+        arr.extend_from_slice(&['a', 'b', 'c', 'd']).unwrap();
+        assert_eq!(arr.pop_front(), Some('a'));
+        assert_eq!(arr.as_slice(), ['b', 'c', 'd']);
+        assert_eq!(arr.remove_first(), Some('b'));
+        assert_eq!(arr.remove_last(), Some('d'));
+        assert_eq!(arr.as_slice(), ['c']);
+        assert_eq!(arr.remove_last(), Some('c'));
+        assert_eq!(arr.remove_first(), None);
+        assert_eq!(arr.remove_last(), None);
+    }
+
+    #[test]
+    fn split_off_tail() {
+        let mut arr = FlexArr::<String>::new();
+        for i in 1..=5 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let tail = arr.split_off_tail(2).unwrap();
+        assert_eq!(arr.as_slice(), ["1", "2"]);
+        assert_eq!(tail.as_slice(), ["3", "4", "5"]);
+        assert_eq!(tail.capacity(), 3);
+
+        let tail = arr.split_off_tail(2).unwrap();
+        assert!(tail.is_empty());
+        assert_eq!(tail.capacity(), 0);
+        assert_eq!(arr.len(), 2);
+
+        let mut arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(1));
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        let err = arr.split_off_tail(0).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn allocator_ref() {
+        let arr = FlexArr::<u8, AllocCount>::new_in(AllocCount::new(3));
+        assert_eq!(arr.allocator_ref().0, 3);
+        assert!(core::ptr::eq(arr.allocator_ref(), FlexArr::allocator(&arr)));
+    }
+
+    #[test]
+    fn clear_no_drop() {
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(4, &drops);
+        arr.truncate_no_drop(5);
+        assert_eq!(arr.len(), 4);
+        arr.truncate_no_drop(2);
+        assert_eq!(arr.len(), 2);
+        assert_eq!(drops.get(), 0);
+        arr.clear_no_drop();
+        assert!(arr.is_empty());
+        assert_eq!(drops.get(), 0);
+        drop(arr);
+        assert_eq!(drops.get(), 0);
+    }
+
+    #[test]
+    fn shrink_to_fit_copy() {
+        struct NoShrink;
+
+        unsafe impl AltAllocator for NoShrink {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                return Global.allocate(layout);
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                unsafe { Global.deallocate(ptr, layout) };
+            }
+            unsafe fn shrink(&self, _: NonNull<u8>, _: Layout, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                return Err(AllocError);
+            }
+        }
+
+        let mut arr = FlexArr::<String, NoShrink>::with_capacity_in(NoShrink, 64).unwrap();
+        for i in 0..5 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let err = arr.shrink_to_fit().unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert_eq!(arr.capacity(), 64);
+
+        arr.shrink_to_fit_copy().unwrap();
+        assert_eq!(arr.capacity(), 5);
+        assert_eq!(arr.as_slice(), ["0", "1", "2", "3", "4"]);
+        arr.push("5".to_string()).unwrap();
+        assert_eq!(arr.len(), 6);
+
+        // Shrinking to nothing does not need to allocate at all.
+        arr.clear();
+        arr.shrink_to_fit_copy().unwrap();
+        assert_eq!(arr.capacity(), 0);
+    }
+
+    #[test]
+    fn extend_from_slice_near_max() {
+        let mut arr = FlexArr::<u8, Global, u8>::new();
+        arr.extend_from_slice(&[7; 200]).unwrap();
+        assert_eq!(arr.len(), 200);
+        arr.extend_from_slice(&[9; 55]).unwrap();
+        assert_eq!(arr.len(), u8::MAX);
+        assert_eq!(arr.capacity(), u8::MAX);
+        assert!(arr[199] == 7 && arr[200] == 9 && arr[254] == 9);
+
+        // Nothing changes when the length would go past the maximum.
+        let err = arr.extend_from_slice(&[1]).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        arr.truncate(100);
+        let err = arr.extend_from_slice(&[1; 300]).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        assert_eq!(arr.len(), 100);
+        arr.extend_from_slice(&[]).unwrap();
+        assert_eq!(arr.len(), 100);
+    }
+
+    #[test]
+    fn with_capacity_zero_matches_vec() {
+        let arr = FlexArr::<u32>::with_capacity(0).unwrap();
+        let vec = Vec::<u32>::with_capacity(0);
+        assert_eq!(arr.capacity() as usize, vec.capacity());
+
+        let arr = FlexArr::<u32>::with_capacity(1).unwrap();
+        assert_eq!(arr.capacity(), 1);
+    }
+
+    #[test]
+    fn iter_copied() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2, 3, 4]).unwrap();
+        let sum: u32 = arr.iter_copied().sum();
+        assert_eq!(sum, 10);
+        assert!(arr.iter_copied().rev().eq([4, 3, 2, 1]));
+        assert_eq!(arr.iter_copied().len(), 4);
+        assert_eq!(arr.len(), 4);
+    }
+
+    #[test]
+    fn reserve_total() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[0; 10]).unwrap();
+        arr.reserve_total(100).unwrap();
+        assert!(arr.capacity() >= 100);
+        let cap = arr.capacity();
+        arr.reserve_total(5).unwrap();
+        arr.reserve_total_exact(cap).unwrap();
+        assert_eq!(arr.capacity(), cap);
+
+        arr.reserve_total_exact(cap + 1).unwrap();
+        assert_eq!(arr.capacity(), cap + 1);
+        assert_eq!(arr.len(), 10);
+    }
+
+    /// An element that counts how many times it is dropped. The tests below check the count
+    /// after each operation, so any double drop or leak shows up as a wrong total.
+    ///
+    /// When `panic_on` is the `id`, dropping it panics after the drop is counted. That way
+    /// the panic safety of the methods that drop elements can be checked too.
+    #[derive(Debug)]
+    struct DropCounter<'a> {
+        id:       u32,
+        drops:    &'a Cell<u32>,
+        panic_on: Option<u32>,
+    }
+
+    impl<'a> DropCounter<'a> {
+        fn new(id: u32, drops: &'a Cell<u32>) -> Self {
+            return Self {
+                id:       id,
+                drops:    drops,
+                panic_on: None,
+            };
+        }
+    }
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+            if self.panic_on == Some(self.id) {
+                panic!("drop panic");
+            }
+        }
+    }
+
+    fn drop_counter_arr(count: u32, drops: &Cell<u32>) -> FlexArr<DropCounter<'_>, Global, u16> {
+        let mut arr = FlexArr::new();
+        for i in 0..count {
+            arr.push(DropCounter::new(i, drops)).unwrap();
+        }
+        return arr;
+    }
+
+    /// The ids of `counters` in order, to check which elements are left.
+    fn counter_ids(counters: &[DropCounter]) -> Vec<u32> {
+        return counters.iter().map(|d| d.id).collect();
+    }
+
+    /// Runs `f` and checks that it panicked. The panic safety tests use this so they only
+    /// have to state what should hold after the unwind.
+    fn assert_panics<F: FnOnce()>(f: F) {
+        let ret = std::panic::catch_unwind(core::panic::AssertUnwindSafe(f));
+        assert!(ret.is_err(), "Expected a panic!");
+    }
+
+    /// The same as `drop_counter_arr()`, but dropping the element with the id `panic_on` panics.
+    fn panic_counter_arr(count: u32, drops: &Cell<u32>, panic_on: u32) -> FlexArr<DropCounter<'_>, Global, u16> {
+        let mut arr = drop_counter_arr(count, drops);
+        for item in arr.iter_mut() {
+            item.panic_on = Some(panic_on);
+        }
+        return arr;
+    }
+
+    #[test]
+    fn drop_counts_single_items() {
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(10, &drops);
+
+        assert_eq!(arr.pop().map(|d| d.id), Some(9));
+        assert_eq!(drops.get(), 1);
+        assert_eq!(arr.remove(0).map(|d| d.id), Some(0));
+        assert_eq!(drops.get(), 2);
+        assert_eq!(arr.swap_remove(1).map(|d| d.id), Some(2));
+        assert_eq!(drops.get(), 3);
+        assert!(arr.remove(100).is_none());
+        assert!(arr.swap_remove(100).is_none());
+        assert_eq!(drops.get(), 3);
+
+        arr.insert(3, DropCounter::new(100, &drops)).unwrap();
+        let err = arr.insert(100, DropCounter::new(101, &drops)).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::IndexOutOfBounds);
+        // The rejected item was dropped, nothing else was.
+        assert_eq!(drops.get(), 4);
+        assert_eq!(counter_ids(&arr), [1, 8, 3, 100, 4, 5, 6, 7]);
+
+        drop(arr);
+        assert_eq!(drops.get(), 4 + 8);
+    }
+
+    #[test]
+    fn drop_counts_bulk() {
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(20, &drops);
+
+        arr.truncate(15);
+        assert_eq!(drops.get(), 5);
+        arr.truncate(30);
+        assert_eq!(drops.get(), 5);
+        arr.truncate_rev(12);
+        assert_eq!(drops.get(), 8);
+        arr.retain(|d| d.id % 2 == 0);
+        assert_eq!(drops.get(), 14);
+        arr.dedup_by(|a, b| a.id / 4 == b.id / 4);
+        assert_eq!(counter_ids(&arr), [0, 4, 8]);
+        assert_eq!(drops.get(), 17);
+
+        let mut other = drop_counter_arr(3, &drops);
+        arr.append(&mut other).unwrap();
+        drop(other);
+        assert_eq!(drops.get(), 17);
+        let tail = arr.split_off_tail(4).unwrap();
+        drop(tail);
+        assert_eq!(drops.get(), 19);
+
+        arr.clear();
+        assert_eq!(drops.get(), 23);
+        arr.resize_with(5, || DropCounter::new(7, &drops)).unwrap();
+        drop(arr);
+        assert_eq!(drops.get(), 28);
+    }
+
+    #[test]
+    fn drop_counts_moves() {
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(8, &drops);
+
+        // Moving the elements around never drops them.
+        arr.reverse();
+        arr.try_sort_by(|a, b| a.id.cmp(&b.id)).unwrap();
+        arr.reserve(100).unwrap();
+        arr.shrink_to_fit().unwrap();
+        assert_eq!(drops.get(), 0);
+
+        let (evens, odds) = arr.partition_in(|d| d.id % 2 == 0, Global).unwrap();
+        assert_eq!(drops.get(), 0);
+        drop(evens);
+        assert_eq!(drops.get(), 4);
+        drop(odds);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn reserve_and_fill() {
+        let mut arr: FlexArr<u8, Global, u16> = FlexArr::new();
+        arr.push(9).unwrap();
+
+        let src = [1u8, 2, 3, 4];
+        arr.reserve_and_fill(16, |spare| {
+            assert!(spare.len() >= 16);
+            for (dst, val) in spare.iter_mut().zip(src) {
+                dst.write(val);
+            }
+            return 4;
+        })
+        .unwrap();
+        assert_eq!(arr.as_slice(), [9, 1, 2, 3, 4]);
+        assert!(arr.capacity() >= 17);
+
+        // Filling nothing leaves the length alone.
+        arr.reserve_and_fill(4, |_| 0).unwrap();
+        assert_eq!(arr.len(), 5);
+
+        let err = arr.reserve_and_fill(u16::MAX, |_| unreachable!()).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        assert_eq!(arr.as_slice(), [9, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reserve_and_fill_overcount() {
+        let mut arr: FlexArr<u8> = FlexArr::new();
+        let _ = arr.reserve_and_fill(4, |_| 5);
+    }
+
+    #[test]
+    fn len_and_capacity_usize() {
+        let mut arr: FlexArr<u8, Global, u16> = FlexArr::new();
+        assert_eq!(arr.len_usize(), 0);
+        assert_eq!(arr.capacity_usize(), 0);
+        arr.reserve_exact(10).unwrap();
+        arr.push(1).unwrap();
+        arr.push(2).unwrap();
+        assert_eq!(arr.len_usize(), 2);
+        assert_eq!(arr.capacity_usize(), arr.capacity() as usize);
+        assert!(arr.capacity_usize() >= 10);
+
+        let mut zst: FlexArr<(), NoAlloc, u16> = FlexArr::new_in(NoAlloc);
+        zst.push(()).unwrap();
+        assert_eq!(zst.len_usize(), 1);
+        assert_eq!(zst.capacity_usize(), u16::MAX as usize);
+
+        // The ZST capacity of a u128 does not fit so it saturates.
+        let wide: FlexArr<(), NoAlloc, u128> = FlexArr::new_in(NoAlloc);
+        assert_eq!(wide.len_usize(), 0);
+        assert_eq!(wide.capacity_usize(), usize::MAX);
+    }
+
+    #[test]
+    fn try_insert_returns_item() {
+        let drops = Cell::new(0);
+        let mut arr: FlexArr<DropCounter, AllocCount, u16> = FlexArr::new_in(AllocCount::new(1));
+        arr.try_insert(0, DropCounter::new(1, &drops)).unwrap();
+        arr.try_insert(0, DropCounter::new(0, &drops)).unwrap();
+
+        let (err, item) = arr.try_insert(5, DropCounter::new(5, &drops)).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::IndexOutOfBounds);
+        assert_eq!(item.id, 5);
+        drop(item);
+
+        // Fill the first allocation, the next insert needs memory the allocator refuses.
+        while arr.len() < arr.capacity() {
+            arr.try_insert(1, DropCounter::new(9, &drops)).unwrap();
+        }
+        let (err, item) = arr.try_insert(1, DropCounter::new(42, &drops)).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+        assert_eq!(item.id, 42);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(arr[0].id, 0);
+        assert_eq!(arr[arr.len() - 1].id, 1);
+    }
+
+    #[test]
+    fn into_rchunks() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        let mut iter = arr.into_rchunks::<2>();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some([4, 5]));
+        assert_eq!(iter.next(), Some([2, 3]));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.remainder(), [1]);
+        assert_eq!(iter.into_remainder().as_slice(), [1]);
+
+        // Unconsumed chunks and the remainder are dropped with the iterator.
+        let drops = Cell::new(0);
+        let arr = drop_counter_arr(7, &drops);
+        let mut iter = arr.into_rchunks::<3>();
+        let chunk = iter.next().unwrap();
+        assert_eq!(chunk.each_ref().map(|d| d.id), [4, 5, 6]);
+        assert_eq!(drops.get(), 0);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+        drop(chunk);
+        assert_eq!(drops.get(), 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn into_rchunks_zero() {
+        let arr: FlexArr<u32> = FlexArr::new();
+        let _ = arr.into_rchunks::<0>();
+    }
+
+    #[test]
+    fn reserve_saturating() {
+        let mut arr: FlexArr<u16, Global, u8> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        let err = arr.reserve(u8::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+        arr.reserve_saturating(u8::MAX).unwrap();
+        assert_eq!(arr.capacity(), u8::MAX);
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+
+        // When it fits it works just like reserve().
+        let mut arr: FlexArr<u16, Global, u8> = FlexArr::new();
+        arr.reserve_saturating(20).unwrap();
+        assert!(arr.capacity() >= 20);
+
+        assert_eq!(LengthType::saturating_add(250u8, 10), u8::MAX);
+        assert_eq!(LengthType::saturating_add(5u64, 10), 15);
+    }
+
+    #[test]
+    fn keep_last() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        for i in 0..10 {
+            arr.push(i).unwrap();
+        }
+        let cap = arr.capacity();
+        arr.keep_last(3);
+        assert_eq!(arr.as_slice(), [7, 8, 9]);
+        assert_eq!(arr.capacity(), cap);
+        arr.keep_last(5);
+        assert_eq!(arr.as_slice(), [7, 8, 9]);
+        arr.keep_last(0);
+        assert!(arr.is_empty());
+
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(6, &drops);
+        arr.keep_last(2);
+        assert_eq!(drops.get(), 4);
+        assert_eq!(counter_ids(&arr), [4, 5]);
+        drop(arr);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn keep_last_panic() {
+        let drops = Cell::new(0);
+        let mut arr = panic_counter_arr(5, &drops, 1);
+        assert_panics(|| arr.keep_last(2));
+        // Every removed element was still dropped and the kept ones moved down.
+        assert_eq!(drops.get(), 3);
+        assert_eq!(counter_ids(&arr), [3, 4]);
+    }
+
+    #[test]
+    fn index_ranges() {
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.extend_from_slice(&[10, 11, 12, 13, 14]).unwrap();
+
+        let view = &arr[1u32..3];
+        assert_eq!(view, &[11, 12]);
+        assert_eq!(view.len(), 2u32);
+        assert_eq!(&arr[..], &[10, 11, 12, 13, 14]);
+        assert_eq!(&arr[3..], &[13, 14]);
+        assert_eq!(&arr[..2], &[10, 11]);
+        assert_eq!(&arr[1..=2], &[11, 12]);
+        assert_eq!(&arr[..=0], &[10]);
+        assert!(arr[5..].is_empty());
+
+        arr[1..3][0u32] = 99;
+        arr[3..].as_mut_slice().fill(0);
+        assert_eq!(arr.as_slice(), [10, 99, 12, 0, 0]);
+    }
+
+    #[test]
+    fn index_range_panics() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+
+        let msg = |f: &dyn Fn()| -> String {
+            let err = std::panic::catch_unwind(core::panic::AssertUnwindSafe(f)).unwrap_err();
+            return err.downcast_ref::<String>().unwrap().clone();
+        };
+        assert_eq!(
+            msg(&|| _ = &arr[1..99]),
+            "Range end index 99 is out of bounds for a length of 3"
+        );
+        assert_eq!(
+            msg(&|| _ = &arr[arr.len() - 1..1]),
+            "Range start index 2 is after the end index 1, the length is 3"
+        );
+        assert_eq!(
+            msg(&|| _ = &arr[..=u16::MAX]),
+            "Range bound overflows the length type, the length is 3"
+        );
+        assert_eq!(
+            msg(&|| _ = &arr[4..]),
+            "Range start index 4 is after the end index 3, the length is 3"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_range_mut_panics() {
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.push(1).unwrap();
+        arr[0..2].as_mut_slice().fill(0);
+    }
+
+    #[test]
+    fn find_byte() {
+        let mut arr: FlexArr<u8, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(b"GET /index HTTP/1.1\r\n").unwrap();
+        assert_eq!(arr.find_byte(b' '), Some(3));
+        assert_eq!(arr.rfind_byte(b' '), Some(10));
+        assert_eq!(arr.find_byte(b'\n'), Some(20));
+        assert_eq!(arr.rfind_byte(b'G'), Some(0));
+        assert_eq!(arr.find_byte(b'#'), None);
+        assert_eq!(arr.rfind_byte(b'#'), None);
+
+        let empty: FlexArr<u8> = FlexArr::new();
+        assert_eq!(empty.find_byte(0), None);
+        assert_eq!(empty.rfind_byte(0), None);
+    }
+
+    #[cfg(feature = "alloc_api2")]
+    #[test]
+    fn api2_box_round_trip() {
+        use allocator_api2::alloc::Global as Api2Global;
+        use allocator_api2::boxed::Box as Api2Box;
+
+        let mut arr: FlexArr<String, Api2Global, u16> = FlexArr::new_in(Api2Global);
+        arr.reserve_exact(10).unwrap();
+        for i in 0..4 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let boxed = arr.try_into_api2_box().unwrap();
+        assert_eq!(boxed.len(), 4);
+        assert_eq!(&boxed[..], ["0", "1", "2", "3"]);
+
+        // Back into a `FlexArr` with an exact capacity.
+        let (raw, alloc) = Api2Box::into_raw_with_allocator(boxed);
+        let ptr2 = NonNull::new(raw as *mut String).unwrap();
+        let arr = unsafe { FlexArr::<String, Api2Global, u16>::try_from_parts(ptr2, 4, 4, alloc) }.unwrap();
+        assert_eq!(arr.as_slice(), ["0", "1", "2", "3"]);
+        assert_eq!(arr.capacity(), 4);
+
+        let empty: FlexArr<u64, Api2Global> = FlexArr::new_in(Api2Global);
+        assert!(empty.try_into_api2_box().unwrap().is_empty());
+        let mut zst: FlexArr<(), Api2Global> = FlexArr::new_in(Api2Global);
+        zst.push(()).unwrap();
+        zst.push(()).unwrap();
+        assert_eq!(zst.try_into_api2_box().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn debug_truncates() {
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(std::format!("{arr:?}"), "[1, 2, 3]");
+
+        let limit = FlexArr::<u32>::DEBUG_ELEMENT_LIMIT;
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        for i in 0..10_000 {
+            arr.push(i).unwrap();
+        }
+        let out = std::format!("{arr:?}");
+        assert!(out.starts_with("[0, 1, 2, "));
+        assert!(out.ends_with(&std::format!("{}, ..., <10000 total>]", limit - 1)));
+        // One separator after each printed element plus the one in the ellipsis.
+        assert_eq!(out.matches(", ").count(), limit + 1);
+
+        // Pushing this many would take forever, but every `()` is the same anyway.
+        let mut zst: FlexArr<(), Global, u64> = FlexArr::new();
+        unsafe { zst.set_len(1 << 40) };
+        assert!(std::format!("{zst:?}").ends_with("(), ..., <1099511627776 total>]"));
+    }
+
+    #[test]
+    fn try_sort_by_key() {
+        let words = [
+            "pear",
+            "apple",
+            "plum",
+            "avocado",
+            "banana",
+            "apricot",
+            "peach",
+            "blueberry",
+        ];
+        let mut arr: FlexArr<String, Global, u16> = FlexArr::new();
+        for _ in 0..4 {
+            for w in words {
+                arr.push(w.to_string()).unwrap();
+            }
+        }
+        let mut expected: Vec<String> = arr.iter().cloned().collect();
+        expected.sort_by_key(|s| s.as_bytes()[0]);
+
+        arr.try_sort_by_key(|s| s.as_bytes()[0]).unwrap();
+        // Words with the same first letter keep the order they were pushed in.
+        assert_eq!(arr.as_slice(), expected.as_slice());
+        assert_eq!(&arr.as_slice()[..3], ["apple", "avocado", "apricot"]);
+    }
+
+    #[test]
+    fn from_slices_in() {
+        let parts: [&[u8]; 3] = [b"GET ", b"/path", b" HTTP/1.1"];
+        let arr = FlexArr::<u8, AllocCount, u16>::from_slices_in(&parts, AllocCount::new(1)).unwrap();
+        assert_eq!(arr.as_slice(), b"GET /path HTTP/1.1");
+        assert_eq!(arr.capacity(), 18);
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        let empty = FlexArr::<u8, NoAlloc>::from_slices_in(&[], NoAlloc).unwrap();
+        assert!(empty.is_empty());
+        let empty = FlexArr::<u8, NoAlloc>::from_slices_in(&[b"", b""], NoAlloc).unwrap();
+        assert!(empty.is_empty());
+
+        let big = [0u8; 200];
+        let err = FlexArr::<u8, Global, u8>::from_slices_in(&[&big, &big], Global).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+    }
+
+    #[test]
+    fn retain_and_shrink() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        for i in 0..1000 {
+            arr.push(i).unwrap();
+        }
+        assert!(arr.capacity() >= 1000);
+        arr.retain_and_shrink(|&x| x % 400 == 0).unwrap();
+        assert_eq!(arr.as_slice(), [0, 400, 800]);
+        assert_eq!(arr.capacity(), 3);
+
+        arr.retain_and_shrink(|_| false).unwrap();
+        assert!(arr.is_empty());
+        assert_eq!(arr.capacity(), 0);
+    }
+
+    #[test]
+    fn extend_from_slice_within_capacity() {
+        let mut arr = FlexArr::<u32, AllocCount, u16>::new_in(AllocCount::new(1));
+        arr.reserve_exact(6).unwrap();
+        arr.extend_from_slice(&[1, 2]).unwrap();
+        let room = arr.capacity() - arr.len();
+        assert_eq!(room, 4);
+
+        let src: [u32; 10] = core::array::from_fn(|i| 10 + i as u32);
+        assert_eq!(arr.extend_from_slice_within_capacity(&src), room);
+        assert_eq!(arr.len(), arr.capacity());
+        assert_eq!(&arr.as_slice()[..6], [1, 2, 10, 11, 12, 13]);
+        assert_eq!(arr.extend_from_slice_within_capacity(&src), 0);
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        let mut empty: FlexArr<u32> = FlexArr::new();
+        assert_eq!(empty.extend_from_slice_within_capacity(&src), 0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.capacity(), 0);
+    }
+
+    #[test]
+    fn reserve_exact_capacity() {
+        // Global does not round up, so the capacity is exactly what was asked for.
+        for additional in [1u32, 3, 7, 9, 100, 1001] {
+            let mut arr = FlexArr::<u64>::new();
+            arr.reserve_exact(additional).unwrap();
+            assert_eq!(arr.capacity(), additional);
+            for i in 0..additional {
+                arr.push(i as u64).unwrap();
+            }
+            // Growing an existing allocation is exact too.
+            arr.reserve_exact(additional).unwrap();
+            assert_eq!(arr.capacity(), additional * 2);
+        }
+
+        // An allocator that rounds up gives at least the request, but no more
+        // than what fits in the block it actually returned.
+        for additional in [1u32, 3, 5, 17] {
+            let mut arr = FlexArr::<u32, RoundUp16>::new_in(RoundUp16);
+            arr.reserve_exact(additional).unwrap();
+            let cap = arr.capacity();
+            assert!(cap >= additional);
+            assert_eq!(cap, (additional * 4).next_multiple_of(16) / 4);
+        }
+    }
+
+    #[test]
+    fn split_owned() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        let (first, rest) = arr.split_first_owned().unwrap();
+        assert_eq!(first, 1);
+        assert_eq!(rest.as_slice(), [2, 3]);
+        let (last, rest) = rest.split_last_owned().unwrap();
+        assert_eq!(last, 3);
+        assert_eq!(rest.as_slice(), [2]);
+
+        fn sum(arr: FlexArr<u32, Global, u16>) -> u32 {
+            let Some((head, rest)) = arr.split_first_owned() else {
+                return 0;
+            };
+            return head + sum(rest);
+        }
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(sum(arr), 10);
+
+        assert!(FlexArr::<u32>::new().split_first_owned().is_none());
+        assert!(FlexArr::<u32>::new().split_last_owned().is_none());
+    }
+
+    // Runs `dedup_by` on DropCounters with the given ids, panicking on comparison
+    // number `panic_on`. Returns the array as the unwind left it.
+    fn dedup_panic_on<'a>(ids: &[u32], panic_on: u32, drops: &'a Cell<u32>) -> FlexArr<DropCounter<'a>, Global, u16> {
+        let mut arr = FlexArr::new();
+        for &id in ids {
+            arr.push(DropCounter::new(id, drops)).unwrap();
+        }
+        let mut calls = 0;
+        assert_panics(|| {
+            arr.dedup_by(|a, b| {
+                calls += 1;
+                if calls == panic_on {
+                    panic!("compare panic");
+                }
+                return a.id == b.id;
+            });
+        });
+        return arr;
+    }
+
+    #[test]
+    fn dedup_by_panic_before_first_duplicate() {
+        // The third comparison happens before any duplicate was found.
+        let drops = Cell::new(0);
+        let arr = dedup_panic_on(&[1, 2, 3, 4, 4, 5], 3, &drops);
+        assert_eq!(counter_ids(&arr), [1, 2, 3, 4, 4, 5]);
+        assert_eq!(drops.get(), 0);
+        drop(arr);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn dedup_by_panic_after_removing() {
+        // The first duplicate is removed, then the third comparison panics. The
+        // unchecked tail is kept, including the duplicate that was never compared.
+        let drops = Cell::new(0);
+        let arr = dedup_panic_on(&[1, 1, 2, 2, 3, 3], 3, &drops);
+        assert_eq!(counter_ids(&arr), [1, 2, 2, 3, 3]);
+        assert_eq!(drops.get(), 1);
+        drop(arr);
+        assert_eq!(drops.get(), 6);
+
+        let drops = Cell::new(0);
+        let arr = dedup_panic_on(&[7, 7, 7, 7, 8], 3, &drops);
+        assert_eq!(counter_ids(&arr), [7, 7, 8]);
+        assert_eq!(drops.get(), 2);
+        drop(arr);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn dedup_by_panic_then_reuse() {
+        let drops = Cell::new(0);
+        let mut arr = dedup_panic_on(&[1, 1, 2, 2, 2, 3], 3, &drops);
+        // The array still works normally after the unwind.
+        arr.dedup_by(|a, b| a.id == b.id);
+        assert_eq!(counter_ids(&arr), [1, 2, 3]);
+        assert_eq!(drops.get(), 3);
+        drop(arr);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn reserve_zeroed() {
+        let spare_bytes = |arr: &mut FlexArr<u32, Global, u16>, count: usize| -> Vec<u8> {
+            let ptr = unsafe { arr.as_mut_ptr().add(arr.len() as usize).cast::<u8>() };
+            let bytes = unsafe { core::slice::from_raw_parts(ptr, count * size_of::<u32>()) };
+            return bytes.to_vec();
+        };
+
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.reserve_exact(4).unwrap();
+        arr.extend_from_slice(&[u32::MAX; 4]).unwrap();
+        // Growing zeroes the new memory and keeps the elements.
+        arr.reserve_zeroed(20).unwrap();
+        assert!(arr.capacity() >= 24);
+        assert_eq!(arr.as_slice(), [u32::MAX; 4]);
+        assert!(spare_bytes(&mut arr, 20).iter().all(|&b| b == 0));
+
+        // Spare capacity that already existed is zeroed as well.
+        arr.extend_from_slice(&[7; 10]).unwrap();
+        arr.truncate(4);
+        assert!(spare_bytes(&mut arr, 10).iter().any(|&b| b != 0));
+        arr.reserve_zeroed(10).unwrap();
+        assert!(spare_bytes(&mut arr, 10).iter().all(|&b| b == 0));
+        assert_eq!(arr.as_slice(), [u32::MAX; 4]);
+
+        let err = arr.reserve_zeroed(u16::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+    }
+
+    #[test]
+    fn drain_both_ends() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        let mut drain = arr.drain(2..8);
+        assert_eq!(drain.len(), 6);
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.next_back(), Some(7));
+        assert_eq!(drain.next(), Some(3));
+        assert_eq!(drain.next_back(), Some(6));
+        assert_eq!(drain.len(), 2);
+        assert_eq!(drain.as_slice(), [4, 5]);
+        drop(drain);
+        assert_eq!(arr.as_slice(), [0, 1, 8, 9]);
+
+        let back: Vec<u32> = arr.drain(..).rev().collect();
+        assert_eq!(back, [9, 8, 1, 0]);
+        assert!(arr.is_empty());
+        assert!(arr.capacity() >= 10);
+
+        // Only the elements left in the middle are dropped, so no string is freed twice.
+        let mut arr: FlexArr<String, Global, u16> = FlexArr::new();
+        for i in 0..8 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let mut drain = arr.drain(1..7);
+        assert_eq!(drain.next().as_deref(), Some("1"));
+        assert_eq!(drain.next_back().as_deref(), Some("6"));
+        assert_eq!(drain.next_back().as_deref(), Some("5"));
+        drop(drain);
+        assert_eq!(arr.as_slice(), ["0", "7"]);
+    }
+
+    #[test]
+    fn drain_keep_rest() {
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(8, &drops);
+        let mut drain = arr.drain(1..6);
+        assert_eq!(drain.next().unwrap().id, 1);
+        assert_eq!(drain.next_back().unwrap().id, 5);
+        assert_eq!(drops.get(), 2);
+        drain.keep_rest();
+        assert_eq!(drops.get(), 2);
+        assert_eq!(counter_ids(&arr), [0, 2, 3, 4, 6, 7]);
+
+        // Dropping the drain drops what was not yielded.
+        let mut drain = arr.drain(1..=3);
+        assert_eq!(drain.next_back().unwrap().id, 4);
+        drop(drain);
+        assert_eq!(drops.get(), 5);
+        assert_eq!(counter_ids(&arr), [0, 6, 7]);
+
+        // Keeping everything leaves the array as it was.
+        arr.drain(..).keep_rest();
+        assert_eq!(arr.len(), 3);
+        drop(arr);
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn drain_zst_and_leak() {
+        // The count lives outside the element so it stays zero sized.
+        std::thread_local! {
+            static DROPS: Cell<u32> = const { Cell::new(0) };
+        }
+        struct Zst;
+        impl Drop for Zst {
+            fn drop(&mut self) {
+                DROPS.with(|d| d.set(d.get() + 1));
+            }
+        }
+        assert_eq!(size_of::<Zst>(), 0);
+        let mut arr: FlexArr<Zst, Global, u16> = FlexArr::new();
+        for _ in 0..6 {
+            arr.push(Zst).unwrap();
+        }
+        let mut drain = arr.drain(1..4);
+        drop(drain.next());
+        drop(drain);
+        assert_eq!(DROPS.with(Cell::get), 3);
+        assert_eq!(arr.len(), 3);
+        let mut drain = arr.drain(..2);
+        drain.next_back();
+        drain.keep_rest();
+        assert_eq!(DROPS.with(Cell::get), 4);
+        assert_eq!(arr.len(), 2);
+
+        // Leaking the drain leaks the elements after the start too, but nothing is dropped twice.
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3, 4]).unwrap();
+        core::mem::forget(arr.drain(1..2));
+        assert_eq!(arr.as_slice(), [1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn drain_out_of_bounds() {
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.push(1).unwrap();
+        let _ = arr.drain(0..2);
+    }
+
+    #[test]
+    fn drain_panic_in_drop() {
+        let drops = Cell::new(0);
+        let mut arr = panic_counter_arr(6, &drops, 2);
+        assert_panics(|| drop(arr.drain(1..4)));
+        assert_eq!(drops.get(), 3);
+        assert_eq!(counter_ids(&arr), [0, 4, 5]);
+    }
+
+    #[test]
+    fn fill_to_capacity() {
+        let mut arr = FlexArr::<u8, AllocCount, u16>::new_in(AllocCount::new(1));
+        arr.reserve_exact(8).unwrap();
+        let ptr = arr.as_ptr();
+        arr.fill_to_capacity(|i| i as u8);
+        assert_eq!(arr.as_slice(), [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(arr.as_ptr(), ptr);
+        assert_eq!(FlexArr::allocator(&arr).1.get(), 1);
+
+        // Already full, so nothing happens.
+        arr.fill_to_capacity(|_| unreachable!());
+        assert_eq!(arr.len(), 8);
+
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.fill_to_capacity(|_| unreachable!());
+        assert!(arr.is_empty());
+
+        let mut zst: FlexArr<(), NoAlloc, u8> = FlexArr::new_in(NoAlloc);
+        let mut calls = 0;
+        zst.fill_to_capacity(|_| calls += 1);
+        assert_eq!(calls, 255);
+        assert_eq!(zst.len(), u8::MAX);
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let arr = FlexArr::<u16, Global, u8>::try_from([1u16, 2, 3].as_slice()).unwrap();
+        assert_eq!(arr.as_slice(), [1, 2, 3]);
+        assert_eq!(arr.capacity(), 3);
+
+        let empty = FlexArr::<u16>::try_from([].as_slice()).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.capacity(), 0);
+
+        let long = [0u16; 256];
+        let err = FlexArr::<u16, Global, u8>::try_from(long.as_slice()).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        let arr: FlexArr<u16, Global, u16> = long.as_slice().try_into().unwrap();
+        assert_eq!(arr.len(), 256);
+    }
+
+    #[test]
+    fn split_at_mut() {
+        let mut arr: FlexArr<u32, Global, u16> = FlexArr::new();
+        arr.extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        let (front, back) = arr.split_at_mut(2);
+        assert_eq!(front.len(), 2u16);
+        assert_eq!(back.len(), 3u16);
+        front[1u16] = 20;
+        back[0u16] = 30;
+        back.as_mut_slice().reverse();
+        assert_eq!(arr.as_slice(), [1, 20, 5, 4, 30]);
+
+        let (front, back) = arr.split_at_mut(5);
+        assert_eq!(front.len(), 5u16);
+        assert!(back.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_at_mut_out_of_bounds() {
+        let mut arr: FlexArr<u32> = FlexArr::new();
+        arr.push(1).unwrap();
+        let _ = arr.split_at_mut(2);
+    }
+
+    #[test]
+    fn push_growth_is_amortized() {
+        // Allow more allocations than should ever be needed, the count is checked below.
+        let mut arr = FlexArr::<u64, AllocCount>::new_in(AllocCount::new(u8::MAX));
+        for i in 0..100_000 {
+            arr.push(i).unwrap();
+        }
+        assert_eq!(arr.len(), 100_000);
+        // Growing by 1.5x from 8 needs about 24 allocations to reach 100_000.
+        // Exact or linear growth would need thousands.
+        let allocs = FlexArr::allocator(&arr).1.get();
+        assert!(allocs < 40, "{allocs} allocations for 100_000 pushes");
+        assert!(allocs > 1);
+    }
+
+    #[test]
+    fn inner_clone_buffer() {
+        let layout = Layout::from_size_align(48, 32).unwrap();
+        let alloc = AllocCount::new(2);
+        let inner = Inner::<AllocCount, u16>::new_in::<u8>(alloc);
+        let mut buf = inner.clone_buffer(10, layout).unwrap();
+        assert_eq!(buf.length, 0);
+        assert_eq!(buf.capacity(layout.size()), 10);
+        assert_eq!(buf.ptr.as_ptr() as usize % 32, 0);
+        assert_eq!(buf.current_layout(layout).unwrap().size(), 10 * 64);
+        // The allocator was cloned before allocating, so the original did not count it.
+        assert_eq!(Inner::allocator(&inner).1.get(), 0);
+        assert_eq!(Inner::allocator(&buf).1.get(), 1);
+        unsafe { buf.deallocate(layout) };
+
+        // Nothing is allocated for no capacity, but the pointer is still aligned.
+        let empty = inner.clone_buffer(0, layout).unwrap();
+        assert_eq!(empty.capacity(layout.size()), 0);
+        assert_eq!(empty.ptr.as_ptr() as usize, 32);
+        assert_eq!(Inner::allocator(&empty).1.get(), 0);
+
+        let full = Inner::<AllocCount, u16>::new_in::<u64>(AllocCount::new(0));
+        let err = full.clone_buffer(4, Layout::new::<u64>()).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::AllocFailure);
+    }
+
+    #[test]
+    fn shrink_to_fit_uses_realloc() {
+        // Forwards shrinking straight to `Global` so it goes through `realloc`,
+        // and counts how many fresh blocks get allocated.
+        struct ReallocShrink(Cell<u8>);
+
+        unsafe impl AltAllocator for ReallocShrink {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                return Global.allocate(layout);
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                unsafe { Global.deallocate(ptr, layout) };
+            }
+            unsafe fn shrink(&self, ptr: NonNull<u8>, old: Layout, new: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                return unsafe { Global.shrink(ptr, old, new) };
+            }
+        }
+
+        let mut arr = FlexArr::<u8, ReallocShrink>::with_capacity_in(ReallocShrink(Cell::new(0)), 1 << 16).unwrap();
+        for i in 0..1000u32 {
+            arr.push(i as u8).unwrap();
+        }
+        assert_eq!(FlexArr::allocator(&arr).0.get(), 1);
+        arr.shrink_to_fit().unwrap();
+        // The shrink never asked for a new block, the old one was resized.
+        assert_eq!(FlexArr::allocator(&arr).0.get(), 1);
+        assert_eq!(arr.capacity(), 1000);
+        assert!(arr.iter().copied().eq((0..1000u32).map(|i| i as u8)));
+
+        // The same end to end with `Global` itself. The pointer may or may
+        // not move, that is up to `realloc`, but the data has to come along.
+        let mut arr = FlexArr::<u8>::new();
+        for i in 0..100_000u32 {
+            arr.push(i as u8).unwrap();
+        }
+        arr.truncate(3000);
+        assert!(arr.capacity() > 3000);
+        arr.shrink_to_fit().unwrap();
+        assert_eq!(arr.capacity(), 3000);
+        assert!(arr.iter().copied().eq((0..3000u32).map(|i| i as u8)));
+        arr.push(1).unwrap();
+        assert_eq!(arr.len(), 3001);
+    }
+
+    #[test]
+    fn into_iter_owned() {
+        let mut arr = FlexArr::<String>::new();
+        for i in 0..5 {
+            arr.push(i.to_string()).unwrap();
+        }
+        let mut iter = arr.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next().as_deref(), Some("0"));
+        assert_eq!(iter.next().as_deref(), Some("1"));
+        assert_eq!(iter.as_slice(), ["2", "3", "4"]);
+        iter.as_mut_slice()[0].push('!');
+        assert_eq!(iter.next_back().as_deref(), Some("4"));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(std::format!("{iter:?}"), r#"IntoIter(["2!", "3"])"#);
+        assert!(iter.eq(["2!", "3"]));
+
+        // Whatever is not yielded is dropped along with the iterator.
+        let drops = Cell::new(0);
+        let arr = drop_counter_arr(6, &drops);
+        let mut iter = arr.into_iter();
+        let first = iter.next().unwrap();
+        let last = iter.next_back().unwrap();
+        assert_eq!((first.id, last.id), (0, 5));
+        assert_eq!(drops.get(), 0);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+        drop((first, last));
+        assert_eq!(drops.get(), 6);
+
+        let arr = drop_counter_arr(3, &drops);
+        let ids: Vec<u32> = arr.into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, [0, 1, 2]);
+        assert_eq!(drops.get(), 9);
+
+        let mut arr = FlexArr::<()>::new();
+        arr.resize(7, ()).unwrap();
+        let mut iter = arr.into_iter();
+        assert_eq!(iter.next_back(), Some(()));
+        assert_eq!(iter.count(), 6);
+    }
+
+    #[test]
+    fn try_extend_unbounded_hint() {
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(u8::MAX));
+        let iter = (0..1000u32).filter(|x| x % 2 == 0);
+        assert_eq!(iter.size_hint(), (0, Some(1000)));
+        arr.try_extend(iter).unwrap();
+        assert_eq!(arr.len(), 500);
+        assert!(arr.iter().copied().eq((0..1000).step_by(2)));
+        // Growing by 1.5x from 8 needs about 11 allocations to reach 500.
+        let allocs = FlexArr::allocator(&arr).1.get();
+        assert!(allocs < 16, "{allocs} allocations for 500 items");
+
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(u8::MAX));
+        let mut n = 0;
+        let iter = core::iter::from_fn(|| {
+            n += 1;
+            return (n <= 500).then_some(n);
+        });
+        assert_eq!(iter.size_hint(), (0, None));
+        arr.try_extend(iter).unwrap();
+        assert_eq!(arr.len(), 500);
+        let allocs = FlexArr::allocator(&arr).1.get();
+        assert!(allocs < 16, "{allocs} allocations for 500 items");
+
+        // An empty iterator does not allocate at all.
+        let mut arr = FlexArr::<u32, AllocCount>::new_in(AllocCount::new(0));
+        arr.try_extend(core::iter::empty()).unwrap();
+        assert_eq!(arr.capacity(), 0);
+    }
+
+    #[test]
+    fn get_disjoint_mut() {
+        let mut arr = FlexArr::<u32, Global, u8>::new();
+        assert_eq!(arr.get_disjoint_mut([]), Some([]));
+        assert_eq!(arr.get_disjoint_mut([0]), None);
+
+        arr.extend_from_slice(&[10, 11, 12, 13, 14]).unwrap();
+        let [a, b, c] = arr.get_disjoint_mut([4, 0, 2]).unwrap();
+        core::mem::swap(a, b);
+        *c += 100;
+        assert_eq!(arr.as_slice(), [14, 11, 112, 13, 10]);
+
+        assert_eq!(arr.get_disjoint_mut([1, 5]), None);
+        assert_eq!(arr.get_disjoint_mut([1, 3, 1]), None);
+
+        // The unchecked version hands out the same references for valid indices.
+        let checked = arr.get_disjoint_mut([3, 1]).map(|refs| refs.map(|r| r as *mut u32));
+        let unchecked = unsafe { arr.get_disjoint_unchecked_mut([3, 1]) }.map(|r| r as *mut u32);
+        assert_eq!(checked, Some(unchecked));
+        let [x, y] = unsafe { arr.get_disjoint_unchecked_mut([3, 1]) };
+        (*x, *y) = (*y, *x);
+        assert_eq!(arr.as_slice(), [14, 13, 112, 11, 10]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn byte_count_overflow_in_length_type() {
+        type Big = [u64; 4];
+        // The last capacity whose size in bytes still fits into a `u64`.
+        let edge = u64::MAX / 32;
+
+        let mut arr = FlexArr::<Big, Global, u64>::new();
+        let err = arr.reserve_exact(edge).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::LayoutFailure);
+        let err = arr.reserve_exact(edge + 1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        let err = arr.reserve(edge + 1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        let err = FlexArr::<Big, Global, u64>::with_capacity(u64::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        assert_eq!(arr.capacity(), 0);
+
+        // A narrow length type can hold capacities whose size in bytes does not fit into
+        // it, since the bytes are only ever counted as a usize.
+        let arr = FlexArr::<Big, Global, u8>::with_capacity(200).unwrap();
+        assert!(arr.capacity() >= 200);
+
+        assert_eq!(LengthType::checked_mul(u64::MAX / 2, 2), Some(u64::MAX - 1));
+        assert_eq!(LengthType::checked_mul(u64::MAX / 2, 3), None);
+        assert_eq!(LengthType::checked_mul(200u8, 2), None);
+    }
+
+    #[test]
+    fn dedup_keep_last() {
+        // Only the value is compared, the tag tells the instances apart.
+        #[derive(Debug)]
+        struct Tagged(u32, char);
+
+        impl PartialEq for Tagged {
+            fn eq(&self, other: &Self) -> bool {
+                return self.0 == other.0;
+            }
+        }
+
+        let items = [
+            (1, 'a'),
+            (1, 'b'),
+            (2, 'c'),
+            (2, 'd'),
+            (2, 'e'),
+            (3, 'f'),
+        ];
+        let mut first = FlexArr::<Tagged>::new();
+        let mut last = FlexArr::<Tagged>::new();
+        for (val, tag) in items {
+            first.push(Tagged(val, tag)).unwrap();
+            last.push(Tagged(val, tag)).unwrap();
+        }
+
+        first.dedup();
+        last.dedup_keep_last();
+        assert_eq!(first.as_slice(), last.as_slice());
+        assert!(first.iter().map(|t| t.1).eq(['a', 'c', 'f']));
+        assert!(last.iter().map(|t| t.1).eq(['b', 'e', 'f']));
+
+        // Nothing happens without any runs.
+        let mut arr = FlexArr::<u32>::new();
+        arr.dedup_keep_last();
+        assert!(arr.is_empty());
+        arr.extend_from_slice(&[3, 1, 3]).unwrap();
+        arr.dedup_keep_last();
+        assert_eq!(arr.as_slice(), [3, 1, 3]);
+    }
+
+    #[test]
+    fn new_const_and_static() {
+        static EMPTY: FlexArr<u32> = FlexArr::new();
+        const EMPTY_IN: FlexArr<String, Global, u8> = FlexArr::new_in(Global);
+        const SMALL: SmallFlexArr<u8, 4> = SmallFlexArr::new();
+
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.capacity(), 0);
+
+        let mut arr = EMPTY_IN;
+        arr.push("const".to_string()).unwrap();
+        assert_eq!(arr.as_slice(), ["const"]);
+
+        let mut small = SMALL;
+        small.push(1).unwrap();
+        assert_eq!(small.as_slice(), [1]);
+    }
+
+    #[test]
+    fn push_get_index() {
+        let mut arr = FlexArr::<&str, Global, u8>::new();
+        assert_eq!(arr.push_get_index("a"), Ok(0));
+        assert_eq!(arr.push_get_index("b"), Ok(1));
+        assert_eq!(arr.push_get_index("c"), Ok(2));
+        assert_eq!(arr[1], "b");
+
+        let mut arr = FlexArr::<(), Global, u8>::new();
+        arr.resize(u8::MAX - 1, ()).unwrap();
+        assert_eq!(arr.push_get_index(()), Ok(u8::MAX - 1));
+        let err = arr.push_get_index(()).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        assert_eq!(arr.len(), u8::MAX);
+    }
+
+    #[test]
+    fn extend_from_slice_first_allocation_exact() {
+        let data: Vec<u32> = (0..1000).collect();
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&data).unwrap();
+        assert_eq!(arr.capacity(), 1000);
+        assert_eq!(arr.as_slice(), data.as_slice());
+
+        // Small slices do not get bumped up to the minimum growth size either.
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(arr.capacity(), 3);
+
+        // Once there is memory, growth is amortized again.
+        arr.extend_from_slice(&[4]).unwrap();
+        assert_eq!(arr.capacity(), 8);
+        arr.extend_from_slice(&data[..5]).unwrap();
+        assert_eq!(arr.capacity(), 12);
+        assert_eq!(arr.len(), 9);
+
+        // Allocators that round up still have their extra room adopted.
+        let mut arr = FlexArr::<u8, RoundUp16>::new_in(RoundUp16);
+        arr.extend_from_slice(&[7; 5]).unwrap();
+        assert_eq!(arr.capacity(), 16);
+    }
+
+    #[test]
+    fn into_iter_copied() {
+        struct FreeCount<'a>(&'a Cell<u32>);
+
+        unsafe impl AltAllocator for FreeCount<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                return Global.allocate(layout);
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.0.set(self.0.get() + 1);
+                unsafe { Global.deallocate(ptr, layout) };
+            }
+        }
+
+        let frees = Cell::new(0);
+        let mut arr = FlexArr::<u16, FreeCount>::new_in(FreeCount(&frees));
+        arr.extend_from_slice(&[1, 2, 3, 4, 5]).unwrap();
+
+        let mut iter = arr.into_iter_copied();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(frees.get(), 0);
+        assert!(iter.by_ref().eq([2, 3, 4]));
+        assert_eq!(iter.next(), None);
+        assert_eq!(frees.get(), 0);
+        drop(iter);
+        assert_eq!(frees.get(), 1);
+
+        // Stopping early still frees the memory once.
+        let mut arr = FlexArr::<u16, FreeCount>::new_in(FreeCount(&frees));
+        arr.extend_from_slice(&[6, 7, 8]).unwrap();
+        assert_eq!(arr.into_iter_copied().take(1).sum::<u16>(), 6);
+        assert_eq!(frees.get(), 2);
+    }
+
+    #[test]
+    fn remaining_capacity_headroom() {
+        let mut arr = FlexArr::<u8, Global, u8>::new();
+        assert_eq!(arr.remaining_capacity_headroom(), u8::MAX);
+        arr.resize(250, 0).unwrap();
+        assert_eq!(arr.remaining_capacity_headroom(), 5);
+        // It does not depend on what is allocated.
+        arr.shrink_to_fit().unwrap();
+        assert_eq!(arr.capacity(), 250);
+        assert_eq!(arr.remaining_capacity_headroom(), 5);
+        arr.resize(u8::MAX, 0).unwrap();
+        assert_eq!(arr.remaining_capacity_headroom(), 0);
+        assert!(arr.push(0).is_err());
+
+        let arr = FlexArr::<u32, Global, u16>::with_capacity(10).unwrap();
+        assert_eq!(arr.remaining_capacity_headroom(), u16::MAX);
+    }
+
+    #[test]
+    fn repeat() {
+        let mut arr = FlexArr::<u32, Global, u16>::new();
+        arr.extend_from_slice(&[1, 2]).unwrap();
+        let rep = arr.repeat(3).unwrap();
+        assert_eq!(rep.as_slice(), [1, 2, 1, 2, 1, 2]);
+        assert_eq!(rep.capacity(), 6);
+        assert!(arr.repeat(0).unwrap().is_empty());
+        assert_eq!(arr.repeat(1).unwrap().as_slice(), [1, 2]);
+
+        arr.push(3).unwrap();
+        let rep = arr.repeat(5).unwrap();
+        assert_eq!(rep.len(), 15);
+        assert!(rep.iter().copied().eq([1, 2, 3].repeat(5)));
+
+        // The length would not fit into the length type.
+        let err = arr.repeat(u16::MAX).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+        let err = arr.repeat(u16::MAX / 3 + 1).unwrap_err();
+        assert_eq!(err.reason(), ErrorReason::CapacityOverflow);
+
+        // Repeating nothing does not allocate, no matter how many times.
+        let empty = FlexArr::<u32, AllocCount, u64>::new_in(AllocCount::new(0));
+        assert!(empty.repeat(u64::MAX).unwrap().is_empty());
+
+        let mut unit = FlexArr::<(), Global, u16>::new();
+        unit.resize(300, ()).unwrap();
+        assert_eq!(unit.repeat(200).unwrap().len(), 60000);
+    }
+
+    #[test]
+    fn retain_compact() {
+        let make = || {
+            let mut arr = FlexArr::<u32>::with_capacity(100).unwrap();
+            arr.extend_from_slice(&(0..100).collect::<Vec<_>>()).unwrap();
+            return arr;
+        };
+
+        let mut arr = make();
+        arr.retain_compact(|x| *x < 10, CompactPolicy::Never).unwrap();
+        assert_eq!(arr.len(), 10);
+        assert_eq!(arr.capacity(), 100);
+
+        let mut arr = make();
+        arr.retain_compact(|x| *x < 10, CompactPolicy::Always).unwrap();
+        assert!(arr.iter().copied().eq(0..10));
+        assert_eq!(arr.capacity(), 10);
+
+        // 100 >> 3 is 12, so 12 elements are not below it but 11 are.
+        let eighth = CompactPolicy::WhenBelowShift(3);
+        let mut arr = make();
+        arr.retain_compact(|x| *x < 12, eighth).unwrap();
+        assert_eq!(arr.capacity(), 100);
+        arr.retain_compact(|x| *x < 11, eighth).unwrap();
+        assert_eq!(arr.capacity(), 11);
+        // A shift of zero shrinks only when some of the capacity is unused.
+        let unused = CompactPolicy::WhenBelowShift(0);
+        arr.retain_compact(|x| *x < 11, unused).unwrap();
+        assert_eq!(arr.capacity(), 11);
+        arr.retain_compact(|x| *x < 9, unused).unwrap();
+        assert_eq!(arr.capacity(), 9);
+
+        let mut arr = make();
+        arr.retain_compact(|_| false, CompactPolicy::WhenBelowShift(2)).unwrap();
+        assert_eq!(arr.capacity(), 0);
+
+        // Shifts too wide for the capacity never shrink, even when empty.
+        let mut arr = make();
+        let never = CompactPolicy::WhenBelowShift(u8::MAX);
+        arr.retain_compact(|_| false, never).unwrap();
+        assert_eq!(arr.capacity(), 100);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "self.len() <= self.capacity()")]
+    fn as_slice_corrupt_length() {
+        // A length past the capacity, built without `set_len()` since that checks it as well.
+        // Nothing is allocated, so not dropping the array does not leak anything.
+        let arr = unsafe { FlexArr::<u32>::from_parts(NonNull::dangling(), 1, 0, Global) };
+        let arr = core::mem::ManuallyDrop::new(arr);
+        let _ = arr.as_slice();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "self.len() <= self.capacity()")]
+    fn as_mut_slice_corrupt_length() {
+        let arr = unsafe { FlexArr::<u32>::from_parts(NonNull::dangling(), 1, 0, Global) };
+        let mut arr = core::mem::ManuallyDrop::new(arr);
+        let _ = arr.as_mut_slice();
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut arr = FlexArr::<u32, Global, u8>::new();
+        assert_eq!(FlexArr::first(&arr), None);
+        assert_eq!(FlexArr::last(&arr), None);
+        assert_eq!(FlexArr::first_mut(&mut arr), None);
+        assert_eq!(FlexArr::last_mut(&mut arr), None);
+
+        arr.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(FlexArr::first(&arr), Some(&1));
+        assert_eq!(FlexArr::last(&arr), Some(&3));
+        *FlexArr::first_mut(&mut arr).unwrap() = 10;
+        *FlexArr::last_mut(&mut arr).unwrap() = 30;
+        assert_eq!(arr.as_slice(), [10, 2, 30]);
+
+        arr.truncate(1);
+        assert_eq!(arr.first(), arr.last());
+    }
+
+    #[test]
+    fn clear_and_truncate_drop_paths() {
+        let mut arr = FlexArr::<u32>::new();
+        arr.extend_from_slice(&(0..100_000).collect::<Vec<_>>()).unwrap();
+        arr.truncate(10);
+        assert!(arr.iter().copied().eq(0..10));
+        arr.clear();
+        assert!(arr.is_empty());
+        assert_eq!(arr.capacity(), 100_000);
+        arr.extend_from_slice(&[7, 8]).unwrap();
+        assert_eq!(arr.as_slice(), [7, 8]);
+
+        // Types that need dropping still have every element dropped.
+        let drops = Cell::new(0);
+        let mut arr = drop_counter_arr(10, &drops);
+        arr.truncate(4);
+        assert_eq!(drops.get(), 6);
+        arr.clear();
+        assert_eq!(drops.get(), 10);
+        assert!(arr.is_empty());
+    }
 }
@@ -36,7 +36,7 @@
 //!   **Note:** This feature should not be enabled with `alloc_unstable` feature. If
 //!   you want to use both just able to enable `alloc_unstable` and `nightly` in the
 //!   `allocator-api2` crate. Additionally, if you are using the `nightly` feature of the
-//!  `allocator-api2` crate you will need to enable the `alloc_unstable` feature.
+//!   `allocator-api2` crate you will need to enable the `alloc_unstable` feature.
 
 #![no_std]
 #![cfg_attr(feature = "alloc_unstable", feature(allocator_api))]
@@ -49,6 +49,9 @@ mod flex_array;
 pub mod types;
 
 pub use flex_array::FlexArr;
+pub use flex_array::FlexSlice;
+pub use flex_array::SmallFlexArr;
+pub use flex_array::iter;
 
 // Kinda annoying I could avoid this with specialization, but I can only have one blanket impl for AltAllocator unless
 // I used specialization. However, I decided against having a specialization flag. Specialization has soundness holes
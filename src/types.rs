@@ -7,8 +7,25 @@
 //! `FlexArrErr` is type that that used to indicate an error during a `FlexArr` operation.
 //!
 //! `FlexArrResult` is a type alias for `Result<T, FlexArrErr>`
+//!
+//! `CollectError` is returned when collecting from a fallible source fails.
+//!
+//! `ReserveError` is returned by `FlexArr::reserve_checked()` to tell apart allocation
+//! failures from capacity overflows.
+//!
+//! `FlexIndex` is a trait implemented for the range types used to get `FlexSlice` views.
+//!
+//! `ReserveInfo` is returned by `FlexArr::reserve_info()` to describe what a reservation did.
+//!
+//! `CompactPolicy` decides if `FlexArr::retain_compact()` shrinks the capacity.
+mod compact_policy;
 mod errors;
+mod flex_index;
 mod len_type;
+mod reserve_info;
 
+pub use compact_policy::CompactPolicy;
 pub use errors::*;
+pub use flex_index::FlexIndex;
 pub use len_type::LengthType;
+pub use reserve_info::ReserveInfo;
@@ -0,0 +1,16 @@
+/// Decides if `FlexArr::retain_compact()` gives memory back after removing elements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompactPolicy {
+    /// Keep the capacity as is.
+    Never,
+    /// Always shrink the capacity to fit the length, like `FlexArr::retain_and_shrink()`.
+    Always,
+    /// Shrink the capacity to fit the length only when the length is below the capacity
+    /// shifted right by this amount, the same way `FlexArr::AUTO_SHRINK_SHIFT` is used.
+    /// For example `WhenBelowShift(2)` shrinks once less than a quarter of the capacity
+    /// is in use.
+    ///
+    /// A shift of `0` shrinks whenever any capacity is unused. A shift as wide as a
+    /// `usize` or wider leaves nothing to be below, so it never shrinks.
+    WhenBelowShift(u8),
+}
@@ -1,3 +1,4 @@
+use core::alloc::Layout;
 use core::error::Error;
 use core::fmt;
 
@@ -17,6 +18,10 @@ pub enum ErrorReason {
 pub type FlexArrResult<T> = Result<T, FlexArrErr>;
 
 /// This is used to indicate an error during a `FlexArr` operation.
+///
+/// It is kept to a single byte so the `FlexArrResult` of common methods like `push()`
+/// stays small. Use `FlexArr::reserve_checked()` to find out the `Layout` the allocator
+/// failed to provide.
 #[repr(transparent)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FlexArrErr(ErrorReason);
@@ -43,3 +48,58 @@ impl fmt::Display for FlexArrErr {
         }
     }
 }
+
+/// This is returned when collecting elements from a fallible source into a `FlexArr`.
+///
+/// It distinguishes between the `FlexArr` failing to allocate or grow, and the source
+/// itself producing an error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CollectError<E> {
+    /// The `FlexArr` failed to reserve space for the elements.
+    Array(FlexArrErr),
+    /// The source produced an error.
+    Source(E),
+}
+
+impl<E> From<FlexArrErr> for CollectError<E> {
+    fn from(err: FlexArrErr) -> Self {
+        return Self::Array(err);
+    }
+}
+
+impl<E: Error> Error for CollectError<E> {}
+
+impl<E: fmt::Display> fmt::Display for CollectError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Array(err) => err.fmt(f),
+            Self::Source(err) => err.fmt(f),
+        }
+    }
+}
+
+/// This is returned by `FlexArr::reserve_checked()`, and tells apart an allocator
+/// failure from a capacity that can not be represented at all.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReserveError {
+    /// The allocator failed to provide memory for the given `Layout`.
+    AllocFailed(Layout),
+    /// The requested capacity does not fit into the `LengthType`, a `usize`, or a valid `Layout`.
+    CapacityOverflow,
+}
+
+impl Error for ReserveError {}
+
+impl fmt::Display for ReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllocFailed(layout) => write!(
+                f,
+                "Failed to allocate memory with a size of {} and an alignment of {}.",
+                layout.size(),
+                layout.align()
+            ),
+            Self::CapacityOverflow => f.write_str("The requested capacity overflowed."),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use core::ops::Range;
+use core::ops::RangeFrom;
+use core::ops::RangeFull;
+use core::ops::RangeInclusive;
+use core::ops::RangeTo;
+use core::ops::RangeToInclusive;
+
+use super::LengthType;
+
+/// This trait is implemented for the range types that can be used to get a `FlexSlice`
+/// view out of a `FlexArr`. Rust's `SliceIndex` trait is sealed, so this takes its place
+/// and allows the bounds to be given using the `LengthType`.
+pub trait FlexIndex<L: LengthType>
+where
+    usize: TryFrom<L>,
+{
+    /// Converts this into a `usize` range after checking it against `len`.
+    ///
+    /// Returns `None` if the range is inverted, goes past `len`, or if any
+    /// bound can not be converted to a `usize`.
+    fn to_range(self, len: L) -> Option<Range<usize>>;
+}
+
+#[inline]
+fn checked_range<L: LengthType>(start: L, end: L, len: L) -> Option<Range<usize>>
+where
+    usize: TryFrom<L>,
+{
+    if start > end || end > len {
+        return None;
+    }
+    let Ok(start) = usize::try_from(start) else {
+        return None;
+    };
+    let Ok(end) = usize::try_from(end) else {
+        return None;
+    };
+    return Some(start..end);
+}
+
+impl<L: LengthType> FlexIndex<L> for Range<L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        return checked_range(self.start, self.end, len);
+    }
+}
+
+impl<L: LengthType> FlexIndex<L> for RangeFrom<L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        return checked_range(self.start, len, len);
+    }
+}
+
+impl<L: LengthType> FlexIndex<L> for RangeTo<L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        return checked_range(L::ZERO_VALUE, self.end, len);
+    }
+}
+
+impl<L: LengthType> FlexIndex<L> for RangeFull
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        return checked_range(L::ZERO_VALUE, len, len);
+    }
+}
+
+impl<L: LengthType> FlexIndex<L> for RangeInclusive<L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        let (start, end) = self.into_inner();
+        let end = end.checked_add(L::ONE_VALUE)?;
+        return checked_range(start, end, len);
+    }
+}
+
+impl<L: LengthType> FlexIndex<L> for RangeToInclusive<L>
+where
+    usize: TryFrom<L>,
+{
+    #[inline]
+    fn to_range(self, len: L) -> Option<Range<usize>> {
+        let end = self.end.checked_add(L::ONE_VALUE)?;
+        return checked_range(L::ZERO_VALUE, end, len);
+    }
+}
@@ -6,6 +6,8 @@ use core::ops;
 /// If you have some other type that you want to use and behaves like an unsigned integer,
 /// you can implement this trait for it.
 ///
+/// # Safety
+///
 /// It's marked as unsafe since your type must be continuous and ordered.
 /// under common operations such as addition multiplication like integers.
 pub unsafe trait LengthType
@@ -49,6 +51,26 @@ where
     fn checked_sub(self, rhs: Self) -> Option<Self>;
     /// The same as `wrapping_add` for rust's built in types.
     fn wrapping_add(self, rhs: Self) -> Self;
+    /// The same as `checked_mul` for rust's built in types.
+    ///
+    /// The default implementation multiplies as `usize`, so it also returns `None`
+    /// when either value or the product does not fit into a `usize`.
+    #[inline]
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let lhs = usize::try_from(self).ok()?;
+        let rhs = usize::try_from(rhs).ok()?;
+        return Self::try_from(lhs.checked_mul(rhs)?).ok();
+    }
+    /// The same as `saturating_add` for rust's built in types.
+    ///
+    /// The default implementation is built on `checked_add()`.
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self {
+        return match self.checked_add(rhs) {
+            Some(val) => val,
+            None => Self::MAX_VALUE,
+        };
+    }
 }
 
 macro_rules! impl_length_type {
@@ -83,6 +105,16 @@ macro_rules! impl_length_type {
             fn wrapping_add(self, rhs: Self) -> Self {
                 return self.wrapping_add(rhs);
             }
+
+            #[inline]
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                return self.checked_mul(rhs);
+            }
+
+            #[inline(always)]
+            fn saturating_add(self, rhs: Self) -> Self {
+                return self.saturating_add(rhs);
+            }
         }
     };
 }
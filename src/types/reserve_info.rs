@@ -0,0 +1,9 @@
+/// Returned by `FlexArr::reserve_info()` to describe what a reservation did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ReserveInfo<L> {
+    /// The capacity after the reservation.
+    pub capacity:    L,
+    /// `true` if the elements were moved to a different address. Memory that the
+    /// allocator managed to grow in place is not counted as reallocated.
+    pub reallocated: bool,
+}